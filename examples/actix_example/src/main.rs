@@ -3,9 +3,21 @@ mod services;
 use crate::services::{ActixService, Repository};
 use actix_web::{web, App, HttpServer};
 use std::sync::Arc;
-use teloc::{DiActixHandler, ServiceProvider};
+use teloc::{ActixScope, EmptyServiceProvider, Resolver, ServiceProvider};
 
-async fn index(service: ActixService<'_>, data: String) -> String {
+type AppProvider = ServiceProvider<
+    EmptyServiceProvider,
+    teloc::reexport::HList![
+        teloc::TransientContainer<ActixService<'static>>,
+        teloc::SingletonContainer<Arc<Repository>>,
+    ],
+>;
+
+// `ActixScope` is a fork of the global `ServiceProvider` with the `HttpRequest` added as an
+// instance. It can be placed at any position in the list of arguments. Dependencies resolved from
+// it can borrow the scope.
+async fn index(data: String, scope: ActixScope<AppProvider>) -> String {
+    let service: ActixService = scope.resolve();
     service.change_and_get_previous(data).await
 }
 
@@ -21,26 +33,14 @@ async fn main() -> std::io::Result<()> {
         .add_singleton::<Arc<Repository>>()
         // Add dependency with a `Transient` lifetime. More about lifetimes see in README.md.
         .add_transient::<ActixService>();
-    // We need to wrap Arc around `ServiceProvider` for thread-safety and cloning.
-    let sp = Arc::new(sp);
+    // We need to wrap Arc around `ServiceProvider` for thread-safety and cloning. It is stored in
+    // the app data and `ActixScope` extractor takes it from there.
+    let sp = web::Data::from(Arc::new(sp));
 
     HttpServer::new(move || {
-        App::new().route(
-            "/",
-            web::post().to(
-                // `DIActixHandler` gives as input a `ServiceProvider` and a handler function and inject
-                // dependencies from the start args in function.
-                DiActixHandler::new(
-                    // Global `ServiceProvider`.
-                    sp.clone(),
-                    // Scope factory that can add scope instances that will be the same between different
-                    // dependencies in one scope.
-                    |s| s,
-                    // Function that will be called for each `HttpRequest`.
-                    index,
-                ),
-            ),
-        )
+        App::new()
+            .app_data(sp.clone())
+            .route("/", web::post().to(index))
     })
     .bind("127.0.0.1:8080")?
     .run()
@@ -0,0 +1,43 @@
+mod services;
+
+use crate::services::{AxumService, Repository};
+use axum::{routing::post, Router};
+use std::sync::Arc;
+use teloc::{AxumScope, EmptyServiceProvider, Resolver, ServiceProvider};
+
+type AppProvider = ServiceProvider<
+    EmptyServiceProvider,
+    teloc::reexport::HList![
+        teloc::TransientContainer<AxumService<'static>>,
+        teloc::SingletonContainer<Repository>,
+    ],
+>;
+
+// `AxumScope` is a fork of the global `ServiceProvider` with the request parts added as an
+// instance. Dependencies resolved from it can borrow the scope.
+async fn index(scope: AxumScope<AppProvider>, data: String) -> String {
+    let service: AxumService = scope.resolve();
+    service.change_and_get_previous(data).await
+}
+
+// For tests you can use curl:
+// ```
+// curl --header "Content-Type: application/json" --data '{"some":"json"}' "127.0.0.1:8080"
+// ```
+#[tokio::main]
+async fn main() -> std::io::Result<()> {
+    // Create the `ServiceProvider` struct that store itself all dependencies.
+    let sp = ServiceProvider::new()
+        // Add dependency with a `Singleton` lifetime. More about lifetimes see in README.md.
+        .add_singleton::<Repository>()
+        // Add dependency with a `Transient` lifetime. More about lifetimes see in README.md.
+        .add_transient::<AxumService>();
+    // We need to wrap Arc around `ServiceProvider` for thread-safety and cloning. It is stored in
+    // the router state and `AxumScope` extractor takes it from there.
+    let sp = Arc::new(sp);
+
+    let app = Router::new().route("/", post(index)).with_state(sp);
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:8080").await?;
+    axum::serve(listener, app).await
+}
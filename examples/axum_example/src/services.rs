@@ -0,0 +1,52 @@
+use axum::http::request::Parts;
+use tokio::sync::{Mutex, MutexGuard};
+
+// Repository stores previous request.
+pub struct Repository {
+    data: Mutex<String>,
+}
+
+// #[inject] macro allow to use `Repository` in `ServiceProvider`
+#[teloc::inject]
+impl Repository {
+    pub fn new() -> Self {
+        Repository {
+            data: Mutex::new(String::new()),
+        }
+    }
+}
+
+impl Repository {
+    pub async fn change(&self, new_data: String) {
+        *self.data.lock().await = new_data;
+    }
+
+    pub async fn get(&self) -> MutexGuard<'_, String> {
+        self.data.lock().await
+    }
+}
+
+// Service that handles requests.
+pub struct AxumService<'a> {
+    store: &'a Repository,
+    parts: &'a Parts,
+}
+
+// #[inject] macro allow to use `AxumService` in `ServiceProvider`
+#[teloc::inject]
+impl<'a> AxumService<'a> {
+    pub fn new(store: &'a Repository, parts: &'a Parts) -> Self {
+        Self { store, parts }
+    }
+}
+
+impl AxumService<'_> {
+    pub async fn change_and_get_previous(&self, new_data: String) -> String {
+        let previous = self.store.get().await.clone();
+        self.store.change(new_data).await;
+        format!(
+            "Request Method: {}\nPrevious request body: {}\n",
+            self.parts.method, previous
+        )
+    }
+}
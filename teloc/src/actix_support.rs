@@ -1,222 +1,238 @@
 //! Support for `actix-web` crate.
-#![allow(unsafe_code)]
 
 use crate::container::InstanceContainer;
 use crate::dependency::DependencyClone;
+use crate::handler::{new_scope, HandlerScope};
 use crate::service_provider::SelectContainer;
-use crate::{Resolver, ServiceProvider};
-use actix_web::dev::*;
+use crate::Resolver;
+use actix_web::dev::{
+    AppConfig, ConnectionInfo, Extensions, Path, Payload, RequestHead, ResourceMap, Url,
+};
+use actix_web::error::ErrorInternalServerError;
 use actix_web::web::Data;
-use actix_web::Responder;
-use actix_web::{http, FromRequest, HttpRequest};
-use frunk::{HCons, HNil};
+use actix_web::{http, FromRequest, HttpMessage, HttpRequest};
+use frunk::{hlist, HList};
+use std::any::type_name;
 use std::cell::Ref;
-use std::future::Future;
-use std::marker::PhantomData;
-use std::pin::Pin;
-use std::ptr::NonNull;
+use std::future::{ready, Ready};
+use std::ops::Deref;
+use std::rc::{Rc, Weak};
 use std::sync::Arc;
 
-/// Struct for inject dependencies from `ServiceProvider` to an actix-web handler function. Works only with
-/// [actix_web::Resource](https://docs.rs/actix-web/3.3.2/actix_web/struct.Resource.html) service.
+/// Type of the `ServiceProvider` that is created for each request. It is a fork of the global
+/// `ServiceProvider` with the `HttpRequest` added as an instance.
+pub type ActixRequestScope<SP> = HandlerScope<SP, HList![HttpRequest]>;
+
+/// Extractor that gives a local scope of the request to an actix-web handler. It can be placed at
+/// any position in the list of arguments of the handler. Dependencies are resolved from the scope
+/// inside of the handler using the `Resolver` trait, so they can borrow the scope and can be used
+/// across `.await` points.
+///
+/// The global `ServiceProvider` must be registered as `web::Data<SP>` in the app data. The scope is
+/// created by forking it when the extractor is used first time for the request, and is stored in
+/// the request extensions, so all extractors of one request share the same scope.
 ///
-/// **IMPORTANT:** dependencies from the `ServiceProvider` must be first in the list of arguments.
+/// Services that do not borrow the scope can also be extracted directly by [`ActixInject`].
 ///
 /// For example you can see [example in git repo](https://github.com/p0lunin/teloc/tree/master/examples/actix_example).
 ///
 /// ## Types that can be got in dependencies
 /// - actix_web::HttpRequest
-/// - &actix_http::RequestHead
+/// - &actix_web::dev::RequestHead
 /// - &actix_web::http::Uri
 /// - &actix_web::http::Method
 /// - actix_web::http::Version
-/// - &actix_web::http::HeaderMap
-/// - &actix_router::Path<actix_router::Url>
-/// - Ref<'_, actix_http::Extensions>
+/// - &actix_web::http::header::HeaderMap
+/// - &actix_web::dev::Path<actix_web::dev::Url>
+/// - Ref<'_, actix_web::dev::Extensions>
 /// - &actix_web::dev::ResourceMap
 /// - Option<std::net::SocketAddr>
-/// - Ref<'_, ConnectionInfo>
-/// - &AppConfig
+/// - Ref<'_, actix_web::dev::ConnectionInfo>
+/// - &actix_web::dev::AppConfig
+/// - Option<&actix_web::web::Data<T>>
 ///
 /// If you want to get something from the `HttpRequest` by a reference you must got one of types from
 /// the list above or `&HttpRequest` (the reference is important due to Rust lifetime checks).
-pub struct DiActixHandler<SP, ScopeFactory, F, ScopeResult, Args, Infers> {
-    sp: Arc<SP>,
-    scope_factory: ScopeFactory,
-    f: F,
-    phantom: PhantomData<(ScopeResult, Args, Infers)>,
+///
+/// Example:
+/// ```
+/// use actix_web::{http::Method, web, App};
+/// use std::sync::Arc;
+/// use teloc::*;
+///
+/// struct Greeter<'a> { method: &'a Method }
+/// #[inject]
+/// impl<'a> Greeter<'a> {
+///     fn new(method: &'a Method) -> Self { Self { method } }
+/// }
+///
+/// type AppProvider = ServiceProvider<EmptyServiceProvider, reexport::HList![TransientContainer<Greeter<'static>>]>;
+///
+/// async fn index(body: String, scope: ActixScope<AppProvider>) -> String {
+///     let greeter: Greeter = scope.resolve();
+///     format!("{} {}", greeter.method, body)
+/// }
+///
+/// let sp = Arc::new(ServiceProvider::new().add_transient::<Greeter>());
+/// let app = App::new()
+///     .app_data(web::Data::from(sp))
+///     .route("/", web::post().to(index));
+/// ```
+pub struct ActixScope<SP>(Rc<ActixRequestScope<SP>>);
+
+impl<SP> ActixScope<SP> {
+    /// Creates a new scope by forking `sp` and adding `req` as an instance.
+    pub fn new(sp: Arc<SP>, req: HttpRequest) -> Self {
+        ActixScope(Rc::new(new_scope(sp, hlist![req])))
+    }
 }
 
-impl<ParSP, DepsSP, ScopeFactory, F, ScopeResult, Args, Infers>
-    DiActixHandler<ServiceProvider<ParSP, DepsSP>, ScopeFactory, F, ScopeResult, Args, Infers>
-where
-    ScopeFactory: Fn(
-            ServiceProvider<
-                Arc<ServiceProvider<ParSP, DepsSP>>,
-                HCons<InstanceContainer<HttpRequest>, HNil>,
-            >,
-        ) -> ScopeResult
-        + Clone
-        + 'static,
-{
-    /// Creates DIActixHandler with specified `ServiceProvider`, scope factory and actix-web handler function.
-    ///
-    /// - `ServiceProvider` is the global provider that can be used between different routes.
-    /// - Scope factory is a function that get local scope and can add some local dependencies that
-    /// will be unique in different requests.
-    /// - handler function is a function that must be called when new `HttpRequest` incoming.
-    pub fn new(sp: Arc<ServiceProvider<ParSP, DepsSP>>, scope_factory: ScopeFactory, f: F) -> Self {
-        DiActixHandler {
-            sp,
-            scope_factory,
-            f,
-            phantom: PhantomData,
-        }
+impl<SP> Clone for ActixScope<SP> {
+    fn clone(&self) -> Self {
+        ActixScope(self.0.clone())
+    }
+}
+
+impl<SP> Deref for ActixScope<SP> {
+    type Target = ActixRequestScope<SP>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
     }
 }
 
-impl<SP, ScopeFactory, F, ScopeResult, Args, Infers> Clone
-    for DiActixHandler<SP, ScopeFactory, F, ScopeResult, Args, Infers>
+/// Request extensions store only a weak reference to the scope, because the scope itself owns
+/// the `HttpRequest`.
+struct SharedScope<SP>(Weak<ActixRequestScope<SP>>);
+
+impl<SP> ActixScope<SP>
 where
-    ScopeFactory: Clone,
-    F: Clone,
+    SP: 'static,
 {
-    fn clone(&self) -> Self {
-        Self {
-            sp: self.sp.clone(),
-            scope_factory: self.scope_factory.clone(),
-            f: self.f.clone(),
-            phantom: PhantomData,
+    /// Gets the scope of the request or creates it if it was not created yet.
+    fn get_or_create(req: &HttpRequest) -> Result<Self, actix_web::Error> {
+        let shared = req
+            .extensions()
+            .get::<SharedScope<SP>>()
+            .and_then(|shared| shared.0.upgrade());
+        if let Some(scope) = shared {
+            return Ok(ActixScope(scope));
         }
+        let sp = match req.app_data::<Data<SP>>() {
+            Some(sp) => sp.clone().into_inner(),
+            None => {
+                return Err(ErrorInternalServerError(
+                    "ServiceProvider is not registered in the app data",
+                ))
+            }
+        };
+        let scope = ActixScope::new(sp, req.clone());
+        req.extensions_mut()
+            .insert(SharedScope(Rc::downgrade(&scope.0)));
+        Ok(scope)
     }
 }
 
-// Safety was checked in https://play.rust-lang.org/?version=nightly&mode=debug&edition=2018&gist=118c918dcf33f7fd15faec185e3bcc4b
-// by miri
-#[pin_project::pin_project(PinnedDrop)]
-pub struct SpFuture<SP, Fut> {
-    sp: *mut SP,
-    #[pin]
-    fut: NonNull<Fut>,
-}
+impl<SP> FromRequest for ActixScope<SP>
+where
+    SP: 'static,
+{
+    type Error = actix_web::Error;
+    type Future = Ready<Result<Self, Self::Error>>;
 
-impl<SP, Fut> SpFuture<SP, Fut> {
-    pub fn new(sp: *mut SP, f: impl FnOnce(*const SP) -> Fut) -> Pin<Box<Self>> {
-        let mut this = Box::pin(SpFuture {
-            sp,
-            fut: NonNull::dangling(),
-        });
-        let fut = Box::leak(Box::new(f(this.sp)));
-        unsafe {
-            let mut_ref: Pin<&mut Self> = this.as_mut();
-            Pin::get_unchecked_mut(mut_ref).fut = NonNull::from(fut);
-        }
-        this
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        ready(Self::get_or_create(req))
     }
 }
 
-#[pin_project::pinned_drop]
-impl<SP, Fut> PinnedDrop for SpFuture<SP, Fut> {
-    fn drop(self: Pin<&mut Self>) {
-        use std::alloc::{dealloc, Layout};
-        unsafe {
-            std::ptr::drop_in_place(self.fut.as_ptr());
-            dealloc(self.fut.as_ptr() as *mut u8, Layout::new::<Fut>());
-            std::ptr::drop_in_place(self.sp);
-            dealloc(self.sp as *mut u8, Layout::new::<SP>());
-        }
+/// Extractor of a service that is owned by the handler, for example `Arc<Repository>` or a
+/// cloneable config. The service is resolved from the [`ActixScope`] of the request by the
+/// [`ActixInjector<T>`] registered in the app data. If it is not registered, the request fails
+/// with `500 Internal Server Error`.
+///
+/// Services that borrow the scope cannot be extracted, use [`ActixScope`] for them.
+///
+/// Example:
+/// ```
+/// use actix_web::{web, App};
+/// use std::sync::Arc;
+/// use teloc::*;
+///
+/// struct Repository { prefix: &'static str }
+///
+/// type AppProvider = ServiceProvider<EmptyServiceProvider, reexport::HList![InstanceContainer<Arc<Repository>>]>;
+///
+/// async fn user(id: web::Path<u32>, ActixInject(repo): ActixInject<Arc<Repository>>) -> String {
+///     format!("{}{}", repo.prefix, id)
+/// }
+///
+/// let sp: Arc<AppProvider> =
+///     Arc::new(ServiceProvider::new().add_instance(Arc::new(Repository { prefix: "user" })));
+/// let app = App::new()
+///     .app_data(web::Data::from(sp))
+///     .app_data(ActixInjector::new(|scope: &ActixRequestScope<AppProvider>| -> Arc<Repository> {
+///         scope.resolve()
+///     }))
+///     .route("/users/{id}", web::get().to(user));
+/// ```
+pub struct ActixInject<T>(pub T);
+
+impl<T> Deref for ActixInject<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
     }
 }
 
-impl<SP, Fut> Future for SpFuture<SP, Fut>
+impl<T> FromRequest for ActixInject<T>
 where
-    Fut: Future,
+    T: 'static,
 {
-    type Output = Fut::Output;
+    type Error = actix_web::Error;
+    type Future = Ready<Result<Self, Self::Error>>;
 
-    fn poll(
-        self: Pin<&mut Self>,
-        cx: &mut std::task::Context<'_>,
-    ) -> std::task::Poll<Self::Output> {
-        let this = self.project();
-        // SAFETY: we just cast NonNull<T> to &'static mut T and use it only to the end of the function.
-        let fut = unsafe { this.fut.map_unchecked_mut(|x| x.as_mut()) };
-        fut.poll(cx)
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let result = match req.app_data::<ActixInjector<T>>() {
+            Some(injector) => (injector.0)(req).map(ActixInject),
+            None => Err(ErrorInternalServerError(format!(
+                "`{}` is not registered by `ActixInjector`",
+                type_name::<T>()
+            ))),
+        };
+        ready(result)
     }
 }
 
-macro_rules! impl_factory_di_args {
-    (($($num:tt, $param:ident),*), $($arg:ident, $cont:ident, $other:ident),*) => {
-        impl<$($param,)* ParSP, DepsSP, ScopeFactory, ScopeResult, F, Res, $($arg, $cont, $other),*>
-            Factory<
-                (HttpRequest, $($param,)*),
-                Pin<Box<SpFuture<ScopeResult, Pin<Box<dyn Future<Output=Res::Output>>>>>>,
-                Res::Output
-            >
-            for DiActixHandler<ServiceProvider<ParSP, DepsSP>, ScopeFactory, F, ScopeResult, ($(($arg,$cont),)*), ($($other,)*)>
-        where
-            (HttpRequest, $($param,)*): FromRequest + 'static,
-            F: 'static,
-            ParSP: 'static,
-            DepsSP: 'static,
-            F: Clone + Fn($($arg,)* $($param),*) -> Res,
-            Res: Future,
-            Res::Output: Responder,
-            ScopeFactory: Fn(ServiceProvider<Arc<ServiceProvider<ParSP, DepsSP>>, HCons<InstanceContainer<HttpRequest>, HNil>>) -> ScopeResult + Clone + 'static,
-            ScopeResult: $(Resolver<'static, &'static $cont, $arg, $other> +)* 'static,
-            Self: 'static,
-        {
-            #[allow(non_snake_case)]
-            #[allow(unused_variables)]
-            fn call(&self, data: (HttpRequest, $($param,)*))
-                -> Pin<Box<SpFuture<ScopeResult, Pin<Box<dyn Future<Output=Res::Output>>>>>>
-            {
-                let (req, $($param,)*) = data;
-                let forked = self.sp.fork_arc().add_instance(req);
-                let scope = Box::new((self.scope_factory)(forked));
-                let ptr = Box::into_raw(scope);
-                SpFuture::new(ptr, move |sp| {
-                    let f = self.f.clone();
-                    $(let $param = $param;)*
-                    Box::pin(async move {
-                        // SAFETY: cast *mut T to &'static mut T is valid because we drop reference early than drop T
-                        // (see impl PinnedDrop for SPFuture)
-                        let sp_ref = unsafe { sp.as_ref() }.unwrap();
-                        $(let $arg = sp_ref.resolve();)*
-                        (f)($($arg,)* $($param),*).await
-                    })
-                } as Pin<Box<dyn Future<Output=Res::Output>>>)
-            }
-        }
+/// Resolves `T` from the scope of the request, so `T` can be extracted by [`ActixInject`]. Register
+/// it in the app data using `App::app_data`.
+pub struct ActixInjector<T>(ResolveFn<T>);
+
+/// Function that resolves `T` from the scope of the request.
+type ResolveFn<T> = Rc<dyn Fn(&HttpRequest) -> Result<T, actix_web::Error>>;
+
+impl<T> ActixInjector<T> {
+    /// Creates the injector from function `f` that resolves `T` from the scope. Usually it is
+    /// `|scope: &ActixRequestScope<SP>| -> T { scope.resolve() }`: the function is needed because
+    /// the path to `T` in the `ServiceProvider` is inferred only at a concrete call of `resolve`.
+    pub fn new<SP, F>(f: F) -> Self
+    where
+        F: Fn(&ActixRequestScope<SP>) -> T + 'static,
+        SP: 'static,
+    {
+        ActixInjector(Rc::new(move |req| {
+            let scope = ActixScope::<SP>::get_or_create(req)?;
+            Ok(f(&scope))
+        }))
     }
 }
 
-macro_rules! impl_factory_di {
-    ($($num:tt, $param:ident),*) => {
-        impl_factory_di_args!(($($num, $param),*),);
-        impl_factory_di_args!(($($num, $param),*), A1, C1, O1);
-        impl_factory_di_args!(($($num, $param),*), A1, C1, O1, A2, C2, O2);
-        impl_factory_di_args!(($($num, $param),*), A1, C1, O1, A2, C2, O2, A3, C3, O3);
-        impl_factory_di_args!(($($num, $param),*), A1, C1, O1, A2, C2, O2, A3, C3, O3, A4, C4, O4);
-        impl_factory_di_args!(($($num, $param),*), A1, C1, O1, A2, C2, O2, A3, C3, O3, A4, C4, O4, A5, C5, O5);
-        impl_factory_di_args!(($($num, $param),*), A1, C1, O1, A2, C2, O2, A3, C3, O3, A4, C4, O4, A5, C5, O5, A6, C6, O6);
-        impl_factory_di_args!(($($num, $param),*), A1, C1, O1, A2, C2, O2, A3, C3, O3, A4, C4, O4, A5, C5, O5, A6, C6, O6, A7, C7, O7);
-        impl_factory_di_args!(($($num, $param),*), A1, C1, O1, A2, C2, O2, A3, C3, O3, A4, C4, O4, A5, C5, O5, A6, C6, O6, A7, C7, O7, A8, C8, O8);
-        impl_factory_di_args!(($($num, $param),*), A1, C1, O1, A2, C2, O2, A3, C3, O3, A4, C4, O4, A5, C5, O5, A6, C6, O6, A7, C7, O7, A8, C8, O8, A9, C9, O9);
-    };
+impl<T> Clone for ActixInjector<T> {
+    fn clone(&self) -> Self {
+        ActixInjector(self.0.clone())
+    }
 }
 
-impl_factory_di!();
-impl_factory_di!(0, B1);
-impl_factory_di!(0, B1, 1, B2);
-impl_factory_di!(0, B1, 1, B2, 2, B3);
-impl_factory_di!(0, B1, 1, B2, 2, B3, 3, B4);
-impl_factory_di!(0, B1, 1, B2, 2, B3, 3, B4, 4, B5);
-impl_factory_di!(0, B1, 1, B2, 2, B3, 3, B4, 4, B5, 5, B6);
-impl_factory_di!(0, B1, 1, B2, 2, B3, 3, B4, 4, B5, 5, B6, 6, B7);
-impl_factory_di!(0, B1, 1, B2, 2, B3, 3, B4, 4, B5, 5, B6, 6, B7, 7, B8);
-impl_factory_di!(0, B1, 1, B2, 2, B3, 3, B4, 4, B5, 5, B6, 6, B7, 7, B8, 8, B9);
-
 impl DependencyClone for HttpRequest {}
 
 pub struct GetRequestData<T>(T);
@@ -237,15 +253,15 @@ macro_rules! impl_resolver_for_request {
 }
 
 impl_resolver_for_request! (
-    (&'a actix_http::RequestHead, |req: &'a HttpRequest| req.head()),
+    (&'a RequestHead, |req: &'a HttpRequest| req.head()),
     (&'a http::Uri, |req: &'a HttpRequest| req.uri()),
     (&'a http::Method, |req: &'a HttpRequest| req.method()),
     (http::Version, |req: &'a HttpRequest| req.version()),
-    (&'a http::HeaderMap, |req: &'a HttpRequest| req.headers()),
-    (&'a actix_router::Path<actix_router::Url>, |req: &'a HttpRequest| req.match_info()),
-    (Ref<'a, actix_http::Extensions>, |req: &'a HttpRequest| req.extensions()),
-    (&'a actix_web::dev::ResourceMap, |req: &'a HttpRequest| req.resource_map()),
-    (Option<std::net::SocketAddr>, |req: &'a HttpRequest| req.head().peer_addr),
+    (&'a http::header::HeaderMap, |req: &'a HttpRequest| req.headers()),
+    (&'a Path<Url>, |req: &'a HttpRequest| req.match_info()),
+    (Ref<'a, Extensions>, |req: &'a HttpRequest| req.extensions()),
+    (&'a ResourceMap, |req: &'a HttpRequest| req.resource_map()),
+    (Option<std::net::SocketAddr>, |req: &'a HttpRequest| req.peer_addr()),
     (Ref<'a, ConnectionInfo>, |req: &'a HttpRequest| req.connection_info()),
     (&'a AppConfig, |req: &'a HttpRequest| req.app_config())
 );
@@ -0,0 +1,262 @@
+//! Support for `axum` crate.
+
+use crate::handler::{new_scope, HandlerScope};
+use axum::extract::{FromRef, FromRequestParts};
+use axum::http::request::Parts;
+use axum::http::{Extensions, Request, StatusCode};
+use frunk::{hlist, HList};
+use std::any::type_name;
+use std::convert::Infallible;
+use std::ops::Deref;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tower_layer::Layer;
+use tower_service::Service;
+
+/// Type of the `ServiceProvider` that is created for each request. It is a fork of the global
+/// `ServiceProvider` with request [`Parts`] added as an instance.
+pub type AxumRequestScope<SP> = HandlerScope<SP, HList![Parts]>;
+
+/// Extractor that gives a local scope of the request to an axum handler. Dependencies are resolved
+/// from the scope inside of the handler using the `Resolver` trait, so they can borrow the scope
+/// and can be used across `.await` points.
+///
+/// Scope is created by the [`AxumScopeLayer`] if it is installed, otherwise it is created by
+/// forking the global `Arc<ServiceProvider>` stored in the router state when the extractor is used first
+/// time for the request. All extractors of one request share the same scope.
+///
+/// Example:
+/// ```
+/// use axum::{routing::get, Router};
+/// use axum::http::request::Parts;
+/// use std::sync::Arc;
+/// use teloc::*;
+///
+/// struct Greeter<'a> { parts: &'a Parts }
+/// #[inject]
+/// impl<'a> Greeter<'a> {
+///     fn new(parts: &'a Parts) -> Self { Self { parts } }
+/// }
+///
+/// type AppProvider = ServiceProvider<EmptyServiceProvider, reexport::HList![TransientContainer<Greeter<'static>>]>;
+///
+/// async fn index(scope: AxumScope<AppProvider>) -> String {
+///     let greeter: Greeter = scope.resolve();
+///     format!("Hello from {}", greeter.parts.uri)
+/// }
+///
+/// let sp = Arc::new(ServiceProvider::new().add_transient::<Greeter>());
+/// let app: Router = Router::new().route("/", get(index)).with_state(sp);
+/// ```
+pub struct AxumScope<SP>(Arc<AxumRequestScope<SP>>);
+
+impl<SP> AxumScope<SP> {
+    /// Creates a new scope by forking `sp` and adding request `parts` as an instance.
+    pub fn new(sp: &Arc<SP>, parts: Parts) -> Self {
+        AxumScope(Arc::new(new_scope(sp.clone(), hlist![parts])))
+    }
+}
+
+impl<SP> Clone for AxumScope<SP> {
+    fn clone(&self) -> Self {
+        AxumScope(self.0.clone())
+    }
+}
+
+impl<SP> Deref for AxumScope<SP> {
+    type Target = AxumRequestScope<SP>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<S, SP> FromRequestParts<S> for AxumScope<SP>
+where
+    S: Send + Sync,
+    Arc<SP>: FromRef<S>,
+    SP: Send + Sync + 'static,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        if let Some(scope) = parts.extensions.get::<AxumScope<SP>>() {
+            return Ok(scope.clone());
+        }
+        let scope = AxumScope::new(&Arc::from_ref(state), parts.clone());
+        parts.extensions.insert(scope.clone());
+        Ok(scope)
+    }
+}
+
+/// Extractor of a service that is owned by the handler, for example `Arc<Repository>` or a
+/// cloneable config. The service is resolved from the [`AxumScope`] of the request by the function
+/// registered for it by [`AxumScopeLayer::inject`], so the layer must be installed. If the service
+/// is not registered, the request is rejected with `500 Internal Server Error`.
+///
+/// Services that borrow the scope cannot be extracted, use [`AxumScope`] for them.
+///
+/// Example:
+/// ```
+/// use axum::{extract::Path, routing::get, Router};
+/// use std::sync::Arc;
+/// use teloc::*;
+///
+/// struct Repository { prefix: &'static str }
+///
+/// async fn user(Path(id): Path<u32>, AxumInject(repo): AxumInject<Arc<Repository>>) -> String {
+///     format!("{}{}", repo.prefix, id)
+/// }
+///
+/// let sp = Arc::new(ServiceProvider::new().add_instance(Arc::new(Repository { prefix: "user" })));
+/// let layer = AxumScopeLayer::new(sp.clone())
+///     .inject(|scope| -> Arc<Repository> { scope.resolve() });
+/// let app: Router = Router::new()
+///     .route("/users/{id}", get(user))
+///     .layer(layer)
+///     .with_state(sp);
+/// ```
+pub struct AxumInject<T>(pub T);
+
+impl<T> Deref for AxumInject<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// Resolves `T` from the scope of the request. Stored in the request extensions by
+/// [`AxumScopeService`] for every type registered by [`AxumScopeLayer::inject`].
+struct Injector<T>(Arc<dyn Fn() -> T + Send + Sync>);
+
+impl<T> Clone for Injector<T> {
+    fn clone(&self) -> Self {
+        Injector(self.0.clone())
+    }
+}
+
+impl<S, T> FromRequestParts<S> for AxumInject<T>
+where
+    S: Send + Sync,
+    T: 'static,
+{
+    type Rejection = (StatusCode, String);
+
+    async fn from_request_parts(parts: &mut Parts, _: &S) -> Result<Self, Self::Rejection> {
+        match parts.extensions.get::<Injector<T>>() {
+            Some(injector) => Ok(AxumInject((injector.0)())),
+            None => Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!(
+                    "`{}` is not registered by `AxumScopeLayer::inject`",
+                    type_name::<T>()
+                ),
+            )),
+        }
+    }
+}
+
+/// Function that stores an `Injector` of one type in the extensions of the request.
+type Register<SP> = Arc<dyn Fn(&AxumScope<SP>, &mut Extensions) + Send + Sync>;
+
+/// `tower` layer that creates an [`AxumScope`] for each request before it goes to the inner
+/// service.
+/// Use it when middlewares must have access to the same scope as the handler, or when services
+/// are extracted by [`AxumInject`].
+pub struct AxumScopeLayer<SP> {
+    sp: Arc<SP>,
+    injectors: Vec<Register<SP>>,
+}
+
+impl<SP> AxumScopeLayer<SP> {
+    pub fn new(sp: Arc<SP>) -> Self {
+        AxumScopeLayer {
+            sp,
+            injectors: vec![],
+        }
+    }
+
+    /// Registers function `f` that resolves `T` from the scope of the request, so `T` can be
+    /// extracted by [`AxumInject`]. Usually it is `|scope| -> T { scope.resolve() }`: the function is
+    /// needed because the path to `T` in the `ServiceProvider` is inferred only at a concrete call
+    /// of `resolve`.
+    pub fn inject<T, F>(mut self, f: F) -> Self
+    where
+        F: Fn(&AxumRequestScope<SP>) -> T + Send + Sync + 'static,
+        T: 'static,
+        SP: Send + Sync + 'static,
+    {
+        let f = Arc::new(f);
+        self.injectors.push(Arc::new(move |scope, extensions| {
+            let (scope, f) = (scope.clone(), f.clone());
+            extensions.insert(Injector(Arc::new(move || f(&scope))));
+        }));
+        self
+    }
+}
+
+impl<SP> Clone for AxumScopeLayer<SP> {
+    fn clone(&self) -> Self {
+        AxumScopeLayer {
+            sp: self.sp.clone(),
+            injectors: self.injectors.clone(),
+        }
+    }
+}
+
+impl<S, SP> Layer<S> for AxumScopeLayer<SP> {
+    type Service = AxumScopeService<S, SP>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        AxumScopeService {
+            inner,
+            sp: self.sp.clone(),
+            injectors: self.injectors.clone(),
+        }
+    }
+}
+
+/// Service created by [`AxumScopeLayer`].
+pub struct AxumScopeService<S, SP> {
+    inner: S,
+    sp: Arc<SP>,
+    injectors: Vec<Register<SP>>,
+}
+
+impl<S, SP> Clone for AxumScopeService<S, SP>
+where
+    S: Clone,
+{
+    fn clone(&self) -> Self {
+        AxumScopeService {
+            inner: self.inner.clone(),
+            sp: self.sp.clone(),
+            injectors: self.injectors.clone(),
+        }
+    }
+}
+
+impl<S, SP, B> Service<Request<B>> for AxumScopeService<S, SP>
+where
+    S: Service<Request<B>>,
+    SP: Send + Sync + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<B>) -> Self::Future {
+        let (mut parts, body) = req.into_parts();
+        let scope = AxumScope::new(&self.sp, parts.clone());
+        for register in &self.injectors {
+            register(&scope, &mut parts.extensions);
+        }
+        parts.extensions.insert(scope);
+        self.inner.call(Request::from_parts(parts, body))
+    }
+}
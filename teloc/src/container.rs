@@ -1,10 +1,18 @@
-use crate::dependency::DependencyClone;
+use crate::dependency::{Constructor, DefaultConstructor, DependencyClone, FamilyMember, Provides};
 use crate::get_dependencies::GetDependencies;
+use crate::locator::Locator;
 use crate::service_provider::SelectContainer;
+use crate::sync::{lock, Mutex, MutexGuard, OnceCell};
 use crate::{Dependency, Resolver};
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::rc::Rc;
+use alloc::sync::Arc;
+use core::any::{Any, TypeId};
+use core::cell::{RefCell, RefMut};
+use core::marker::PhantomData;
+use core::ops::{Deref, DerefMut};
 use frunk::HNil;
-use once_cell::sync::OnceCell;
-use std::marker::PhantomData;
 
 /// Init is a trait used in [`ServiceProvider`] for create an empty version of `Container`. If you
 /// create your own version of container and you want that it can work with other container like
@@ -23,86 +31,113 @@ pub trait ResolveContainer<'a, T, Deps> {
     fn resolve_container<F: Fn() -> Deps>(ct: &'a Self, deps: F) -> T;
 }
 
+// `fn() -> _` is used so that the container is `Send` and `Sync` for any service, because it does
+// not store services.
 #[derive(Debug)]
-pub struct TransientContainer<T>(PhantomData<T>);
-impl<T> Container for TransientContainer<T> {
+pub struct TransientContainer<T, Ctor = DefaultConstructor>(PhantomData<fn() -> (T, Ctor)>);
+impl<T, Ctor> Container for TransientContainer<T, Ctor> {
     type Data = ();
 
     fn init(_: ()) -> Self {
+        Self::new()
+    }
+}
+impl<T, Ctor> TransientContainer<T, Ctor> {
+    pub const fn new() -> Self {
         Self(PhantomData)
     }
 }
-impl<'a, T, Deps> ResolveContainer<'a, T, Deps> for TransientContainer<T>
+impl<T, Ctor> Default for TransientContainer<T, Ctor> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<'a, T, Ctor, Deps> ResolveContainer<'a, T, Deps> for TransientContainer<T, Ctor>
 where
-    T: Dependency<Deps>,
+    Ctor: Constructor<T, Deps>,
 {
     fn resolve_container<F: Fn() -> Deps>(_: &'a Self, get_deps: F) -> T {
-        T::init(get_deps())
+        Ctor::construct(get_deps())
     }
 }
-impl<'this, 'cont, T, SP, Index, Deps, Infer>
-    Resolver<'this, &'cont TransientContainer<T>, T, (Index, Deps, Infer)> for SP
+impl<'this, 'cont, T, Ctor, SP, Index, Deps, Infer>
+    Resolver<'this, &'cont TransientContainer<T, Ctor>, T, (Index, Deps, Infer)> for SP
 where
-    SP: SelectContainer<'this, &'cont TransientContainer<T>, Index>
+    SP: SelectContainer<'this, &'cont TransientContainer<T, Ctor>, Index>
         + GetDependencies<'this, Deps, Infer>,
-    TransientContainer<T>: ResolveContainer<'cont, T, Deps>,
-    T: Dependency<Deps> + 'cont,
+    TransientContainer<T, Ctor>: ResolveContainer<'cont, T, Deps>,
+    Ctor: Constructor<T, Deps> + 'cont,
+    T: 'cont,
 {
     fn resolve(&'this self) -> T {
         TransientContainer::resolve_container(self.get(), || self.get_deps())
     }
 }
 
+/// Container of a singleton service. Like in `TransientContainer`, `Ctor` chooses the constructor
+/// of the service.
 #[derive(Debug)]
-pub struct SingletonContainer<T>(OnceCell<T>);
-impl<T> Container for SingletonContainer<T> {
+pub struct SingletonContainer<T, Ctor = DefaultConstructor>(OnceCell<T>, PhantomData<fn() -> Ctor>);
+impl<T, Ctor> Container for SingletonContainer<T, Ctor> {
     type Data = ();
 
     fn init(_: ()) -> Self {
-        Self(OnceCell::new())
+        Self::new()
     }
 }
-impl<'a, T, Deps> ResolveContainer<'a, &'a T, Deps> for SingletonContainer<T>
+impl<'a, T, Ctor, Deps> ResolveContainer<'a, &'a T, Deps> for SingletonContainer<T, Ctor>
 where
-    T: Dependency<Deps> + 'a,
+    Ctor: Constructor<T, Deps>,
+    T: 'a,
 {
     fn resolve_container<F: Fn() -> Deps>(ct: &'a Self, get_deps: F) -> &'a T {
-        ct.get().get_or_init(|| T::init(get_deps()))
+        ct.get().get_or_init(|| Ctor::construct(get_deps()))
     }
 }
 
-impl<'this, 'cont, T, SP, Index, Deps, Infer>
-    Resolver<'this, &'cont SingletonContainer<T>, T, (Index, Deps, Infer)> for SP
+impl<'this, 'cont, T, Ctor, SP, Index, Deps, Infer>
+    Resolver<'this, &'cont SingletonContainer<T, Ctor>, T, (Index, Deps, Infer)> for SP
 where
     SP: GetDependencies<'this, Deps, Infer>
-        + SelectContainer<'this, &'cont SingletonContainer<T>, Index>,
-    SingletonContainer<T>: ResolveContainer<'cont, &'cont T, Deps>,
-    T: Dependency<Deps> + DependencyClone + 'cont,
+        + SelectContainer<'this, &'cont SingletonContainer<T, Ctor>, Index>,
+    SingletonContainer<T, Ctor>: ResolveContainer<'cont, &'cont T, Deps>,
+    Ctor: Constructor<T, Deps> + 'cont,
+    T: DependencyClone + 'cont,
     Deps: 'cont,
 {
     fn resolve(&'this self) -> T {
         SingletonContainer::resolve_container(self.get(), || self.get_deps()).clone()
     }
 }
-impl<'this, 'cont, T, SP, Index, Deps, Infer>
-    Resolver<'this, &'cont SingletonContainer<T>, &'cont T, (Index, Deps, Infer)> for SP
+impl<'this, 'cont, T, Ctor, SP, Index, Deps, Infer>
+    Resolver<'this, &'cont SingletonContainer<T, Ctor>, &'cont T, (Index, Deps, Infer)> for SP
 where
     SP: GetDependencies<'this, Deps, Infer>
-        + SelectContainer<'this, &'cont SingletonContainer<T>, Index>,
-    SingletonContainer<T>: ResolveContainer<'cont, &'cont T, Deps>,
-    T: Dependency<Deps> + 'cont,
+        + SelectContainer<'this, &'cont SingletonContainer<T, Ctor>, Index>,
+    SingletonContainer<T, Ctor>: ResolveContainer<'cont, &'cont T, Deps>,
+    Ctor: Constructor<T, Deps> + 'cont,
+    T: 'cont,
     Deps: 'cont,
 {
     fn resolve(&'this self) -> &'cont T {
         SingletonContainer::resolve_container(self.get(), || self.get_deps())
     }
 }
-impl<T> SingletonContainer<T> {
+impl<T, Ctor> SingletonContainer<T, Ctor> {
+    pub const fn new() -> Self {
+        Self(OnceCell::new(), PhantomData)
+    }
+
     #[inline]
     pub fn get(&self) -> &OnceCell<T> {
         &self.0
     }
 }
+impl<T, Ctor> Default for SingletonContainer<T, Ctor> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 #[derive(Debug)]
 pub struct InstanceContainer<T>(T);
@@ -110,7 +145,7 @@ impl<T> Container for InstanceContainer<T> {
     type Data = T;
 
     fn init(instance: T) -> Self {
-        Self(instance)
+        Self::new(instance)
     }
 }
 impl<'a, T> ResolveContainer<'a, &'a T, HNil> for InstanceContainer<T> {
@@ -139,12 +174,84 @@ where
     }
 }
 impl<T> InstanceContainer<T> {
+    pub const fn new(instance: T) -> Self {
+        Self(instance)
+    }
+
     #[inline]
     pub fn get(&self) -> &T {
         &self.0
     }
 }
 
+/// Container for an instance that can be borrowed mutably through [`InstanceMut`] guard. Borrows
+/// are checked at runtime like in `RefCell`.
+#[derive(Debug)]
+pub struct InstanceMutContainer<T>(RefCell<T>);
+impl<T> Container for InstanceMutContainer<T> {
+    type Data = T;
+
+    fn init(instance: T) -> Self {
+        Self::new(instance)
+    }
+}
+impl<'a, T> ResolveContainer<'a, &'a RefCell<T>, HNil> for InstanceMutContainer<T> {
+    fn resolve_container<F: Fn() -> HNil>(ct: &'a InstanceMutContainer<T>, _: F) -> &'a RefCell<T> {
+        &ct.0
+    }
+}
+impl<'this, 'cont, T, SP, Index>
+    Resolver<'this, &'cont InstanceMutContainer<T>, &'cont RefCell<T>, Index> for SP
+where
+    SP: SelectContainer<'this, &'cont InstanceMutContainer<T>, Index>,
+{
+    fn resolve(&'this self) -> &'cont RefCell<T> {
+        InstanceMutContainer::resolve_container(self.get(), || HNil)
+    }
+}
+impl<'this, 'cont, T, SP, Index>
+    Resolver<'this, &'cont InstanceMutContainer<T>, InstanceMut<'cont, T>, Index> for SP
+where
+    SP: SelectContainer<'this, &'cont InstanceMutContainer<T>, Index>,
+{
+    fn resolve(&'this self) -> InstanceMut<'cont, T> {
+        match self.get().0.try_borrow_mut() {
+            Ok(guard) => InstanceMut(guard),
+            Err(_) => panic!(
+                "`{}` is already borrowed: drop the previous `InstanceMut` before resolving it again",
+                core::any::type_name::<T>()
+            ),
+        }
+    }
+}
+impl<T> InstanceMutContainer<T> {
+    pub const fn new(instance: T) -> Self {
+        Self(RefCell::new(instance))
+    }
+
+    #[inline]
+    pub fn get(&self) -> &RefCell<T> {
+        &self.0
+    }
+}
+
+/// Guard that gives an exclusive access to the instance added by
+/// `ServiceProvider::add_instance_mut`. The instance is released when the guard is dropped.
+#[derive(Debug)]
+pub struct InstanceMut<'a, T>(RefMut<'a, T>);
+impl<T> Deref for InstanceMut<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+impl<T> DerefMut for InstanceMut<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
 pub struct ConvertContainer<Cont, T, U>(Cont, PhantomData<(T, U)>);
 impl<Cont, T, U> Container for ConvertContainer<Cont, T, U>
 where
@@ -186,3 +293,261 @@ impl<Cont, ContT, T> ConvertContainer<Cont, ContT, T> {
         &self.0
     }
 }
+
+/// Container that converts services of type `T` created by the `Cont` container into the trait
+/// object `I` using [`Provides`] trait. `TransientContainer<T>` gives `Box<I>`,
+/// `SingletonContainer<Arc<T>>` gives `Arc<I>`.
+pub struct ProvideContainer<Cont, T, I: ?Sized>(Cont, ProvidePhantom<T, I>);
+
+// `fn() -> Box<I>` is used to not require `Send` and `Sync` from the trait object.
+type ProvidePhantom<T, I> = PhantomData<(T, fn() -> Box<I>)>;
+impl<Cont, T, I> Container for ProvideContainer<Cont, T, I>
+where
+    Cont: Container,
+    I: ?Sized,
+{
+    type Data = Cont::Data;
+
+    fn init(data: Self::Data) -> Self {
+        Self(Cont::init(data), PhantomData)
+    }
+}
+impl<'a, T, Ctor, I, Deps> ResolveContainer<'a, Box<I>, Deps>
+    for ProvideContainer<TransientContainer<T, Ctor>, T, I>
+where
+    TransientContainer<T, Ctor>: ResolveContainer<'a, T, Deps>,
+    T: Provides<I>,
+    I: ?Sized,
+{
+    fn resolve_container<F: Fn() -> Deps>(ct: &'a Self, deps: F) -> Box<I> {
+        Box::new(TransientContainer::resolve_container(&ct.0, deps)).provide_box()
+    }
+}
+impl<'a, T, I, Deps> ResolveContainer<'a, Arc<I>, Deps>
+    for ProvideContainer<SingletonContainer<Arc<T>>, Arc<T>, I>
+where
+    SingletonContainer<Arc<T>>: ResolveContainer<'a, &'a Arc<T>, Deps>,
+    T: Provides<I> + 'a,
+    I: ?Sized,
+{
+    fn resolve_container<F: Fn() -> Deps>(ct: &'a Self, deps: F) -> Arc<I> {
+        SingletonContainer::resolve_container(&ct.0, deps)
+            .clone()
+            .provide_arc()
+    }
+}
+impl<'this, 'cont, T, Ctor, I, SP, Index, Deps, Infer>
+    Resolver<
+        'this,
+        &'cont ProvideContainer<TransientContainer<T, Ctor>, T, I>,
+        Box<I>,
+        (Index, Deps, Infer),
+    > for SP
+where
+    SP: SelectContainer<'this, &'cont ProvideContainer<TransientContainer<T, Ctor>, T, I>, Index>
+        + GetDependencies<'this, Deps, Infer>,
+    ProvideContainer<TransientContainer<T, Ctor>, T, I>: ResolveContainer<'cont, Box<I>, Deps>,
+    T: 'cont,
+    Ctor: 'cont,
+    I: ?Sized + 'cont,
+{
+    fn resolve(&'this self) -> Box<I> {
+        ProvideContainer::resolve_container(self.get(), || self.get_deps())
+    }
+}
+impl<'this, 'cont, T, I, SP, Index, Deps, Infer>
+    Resolver<
+        'this,
+        &'cont ProvideContainer<SingletonContainer<Arc<T>>, Arc<T>, I>,
+        Arc<I>,
+        (Index, Deps, Infer),
+    > for SP
+where
+    SP: SelectContainer<
+            'this,
+            &'cont ProvideContainer<SingletonContainer<Arc<T>>, Arc<T>, I>,
+            Index,
+        > + GetDependencies<'this, Deps, Infer>,
+    ProvideContainer<SingletonContainer<Arc<T>>, Arc<T>, I>: ResolveContainer<'cont, Arc<I>, Deps>,
+    T: 'cont,
+    I: ?Sized + 'cont,
+    Deps: 'cont,
+{
+    fn resolve(&'this self) -> Arc<I> {
+        ProvideContainer::resolve_container(self.get(), || self.get_deps())
+    }
+}
+impl<Cont, T, I: ?Sized> ProvideContainer<Cont, T, I> {
+    #[inline]
+    pub fn get(&self) -> &Cont {
+        &self.0
+    }
+}
+
+/// Container that has no own storage and forwards resolving to the container of the pointer `P`
+/// (`Arc<T>` or `Rc<T>`) registered in the same `ServiceProvider`, converting the resolved pointer
+/// into the pointer to the trait object `I` using [`Provides`] trait. So several trait objects can
+/// be backed by the same singleton.
+pub struct ForwardContainer<P, I: ?Sized>(ProvidePhantom<P, I>);
+impl<P, I: ?Sized> Container for ForwardContainer<P, I> {
+    type Data = ();
+
+    fn init(_: ()) -> Self {
+        Self(PhantomData)
+    }
+}
+
+macro_rules! impl_resolver_for_forward {
+    ($($ptr:ident, $provide:ident),*) => {
+        $(
+        impl<'this, 'cont, T, I, SP, Index, Cont, Infer>
+            Resolver<'this, &'cont ForwardContainer<$ptr<T>, I>, $ptr<I>, (Index, Cont, Infer)>
+            for SP
+        where
+            SP: SelectContainer<'this, &'cont ForwardContainer<$ptr<T>, I>, Index>
+                + Resolver<'this, Cont, $ptr<T>, Infer>,
+            T: Provides<I>,
+            I: ?Sized,
+        {
+            fn resolve(&'this self) -> $ptr<I> {
+                Resolver::<'this, Cont, $ptr<T>, Infer>::resolve(self).$provide()
+            }
+        }
+        )*
+    };
+}
+
+impl_resolver_for_forward!(Arc, provide_arc, Rc, provide_rc);
+
+/// Container that allows to resolve the `ServiceProvider` that resolves a service, as
+/// `&ServiceProvider<..>` or as type-erased `&dyn Locator`. When it is added to a parent, forks
+/// resolve themselves, not the parent.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LocatorContainer;
+impl Container for LocatorContainer {
+    type Data = ();
+
+    fn init(_: ()) -> Self {
+        Self::new()
+    }
+}
+impl<'this, 'cont, SP, Index> Resolver<'this, &'cont LocatorContainer, &'this SP, Index> for SP
+where
+    SP: SelectContainer<'this, &'cont LocatorContainer, Index>,
+{
+    fn resolve(&'this self) -> &'this SP {
+        self
+    }
+}
+impl<'this, 'cont, SP, Index> Resolver<'this, &'cont LocatorContainer, &'this dyn Locator, Index>
+    for SP
+where
+    SP: SelectContainer<'this, &'cont LocatorContainer, Index> + Locator,
+{
+    fn resolve(&'this self) -> &'this dyn Locator {
+        self
+    }
+}
+impl LocatorContainer {
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+/// Cell of one member of a family, type-erased to be stored in `GenericContainer`.
+trait GenericInstance: Send + Sync {
+    fn is_initialised(&self) -> bool;
+    fn into_any(self: Arc<Self>) -> Arc<dyn Any + Send + Sync>;
+}
+impl<M> GenericInstance for OnceCell<Arc<M>>
+where
+    M: Send + Sync + 'static,
+{
+    fn is_initialised(&self) -> bool {
+        self.get().is_some()
+    }
+
+    fn into_any(self: Arc<Self>) -> Arc<dyn Any + Send + Sync> {
+        self
+    }
+}
+
+type GenericInstances = BTreeMap<TypeId, Arc<dyn GenericInstance>>;
+
+/// Container for all services which `FamilyMember::Family` is `F`. Each of them is created once,
+/// like in `SingletonContainer`, and is resolved as `Arc<Service>`.
+pub struct GenericContainer<F>(Mutex<GenericInstances>, PhantomData<fn() -> F>);
+impl<F> Container for GenericContainer<F> {
+    type Data = ();
+
+    fn init(_: ()) -> Self {
+        Self::new()
+    }
+}
+impl<'this, 'cont, F, M, SP, Index, Deps, Infer>
+    Resolver<'this, &'cont GenericContainer<F>, Arc<M>, (Index, Deps, Infer)> for SP
+where
+    SP: GetDependencies<'this, Deps, Infer>
+        + SelectContainer<'this, &'cont GenericContainer<F>, Index>,
+    M: FamilyMember<Family = F> + Dependency<Deps> + Send + Sync + 'static,
+    F: 'cont,
+{
+    fn resolve(&'this self) -> Arc<M> {
+        // Lock of the container is not held while the service is created, because it can depend
+        // on another member of the same family. The cell guarantees that it is created once.
+        let cell = self.get().cell::<M>();
+        cell.get_or_init(|| Arc::new(M::init(self.get_deps())))
+            .clone()
+    }
+}
+impl<F> GenericContainer<F> {
+    pub const fn new() -> Self {
+        Self(Mutex::new(BTreeMap::new()), PhantomData)
+    }
+
+    /// Returns an instance of `M` if it is already created.
+    pub fn get<M>(&self) -> Option<Arc<M>>
+    where
+        M: Send + Sync + 'static,
+    {
+        let cell = self.instances().get(&TypeId::of::<M>())?.clone();
+        let cell = cell.into_any().downcast::<OnceCell<Arc<M>>>().ok()?;
+        cell.get().cloned()
+    }
+
+    /// Returns count of already created instances.
+    pub fn len(&self) -> usize {
+        self.instances()
+            .values()
+            .filter(|cell| cell.is_initialised())
+            .count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn instances(&self) -> MutexGuard<'_, GenericInstances> {
+        lock(&self.0)
+    }
+
+    /// Returns the cell of `M`, inserting an empty one if there is no cell yet.
+    fn cell<M>(&self) -> Arc<OnceCell<Arc<M>>>
+    where
+        M: Send + Sync + 'static,
+    {
+        let cell = self
+            .instances()
+            .entry(TypeId::of::<M>())
+            .or_insert_with(|| Arc::new(OnceCell::<Arc<M>>::new()))
+            .clone();
+        cell.into_any()
+            .downcast()
+            .unwrap_or_else(|_| unreachable!("cells are stored by `TypeId` of their members"))
+    }
+}
+impl<F> Default for GenericContainer<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
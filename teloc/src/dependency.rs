@@ -1,14 +1,154 @@
-use std::cell::RefCell;
-use std::rc::Rc;
-use std::sync::Arc;
+use alloc::boxed::Box;
+use alloc::rc::Rc;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use core::cell::RefCell;
+use core::fmt::Display;
+#[cfg(feature = "std")]
+use std::sync::{Mutex, RwLock};
 
 /// Trait is used to working with `Resolver` trait. If you want that your service can be resolved by
 /// `Resolver`, you may implement this trait for your service. There are three ways:
 /// 1. Implement it by yourself. Not recommended for production code, recommended for library code.
 /// 2. Create a constructor and add `#[inject]` macro. Recommended for production code.
 /// 3. Derive `Teloc` macro, when all of your fields of structs implement `Dependency`.
+///
+/// Derive macro works with structs and enums with one variant marked by `#[inject_variant]`:
+/// ```
+/// use teloc::*;
+///
+/// #[derive(Dependency)]
+/// struct Foo;
+///
+/// #[derive(Dependency)]
+/// struct Bar(Foo);
+///
+/// #[derive(Dependency)]
+/// enum Baz {
+///     #[inject_variant]
+///     Bar(Bar),
+///     Empty,
+/// }
+/// ```
+///
+/// Enums without marked variant cannot be derived:
+/// ```compile_fail
+/// use teloc::*;
+///
+/// #[derive(Dependency)]
+/// enum Baz {
+///     Foo,
+///     Empty,
+/// }
+/// ```
+///
+/// Enums with more than one marked variant cannot be derived:
+/// ```compile_fail
+/// use teloc::*;
+///
+/// #[derive(Dependency)]
+/// enum Baz {
+///     #[inject_variant]
+///     Foo,
+///     #[default_variant]
+///     Empty,
+/// }
+/// ```
+///
+/// Unions cannot be derived:
+/// ```compile_fail
+/// use teloc::*;
+///
+/// #[derive(Dependency)]
+/// union Baz {
+///     foo: u8,
+///     bar: u16,
+/// }
+/// ```
 pub trait Dependency<Deps> {
     fn init(deps: Deps) -> Self;
+
+    /// Returns a message of the error if the created service represents a failure of its
+    /// initialisation. Singletons for which it returns `Some` are reported in the error of
+    /// `ServiceProvider::init_singletons`. Returns `None` by default.
+    fn init_failure(&self) -> Option<String> {
+        None
+    }
+}
+
+/// Fallible version of `Dependency`. A service that implements it can be registered as
+/// `Result<Service, Error>`, and `ServiceProvider::init_singletons` reports singletons that are
+/// `Err` without panicking:
+/// ```
+/// use teloc::*;
+///
+/// struct Config { url: &'static str }
+///
+/// struct Pool;
+/// impl<'a> TryDependency<reexport::HList![&'a Config]> for Pool {
+///     type Error = String;
+///
+///     fn try_init(reexport::hlist_pat![config]: reexport::HList![&'a Config]) -> Result<Self, String> {
+///         Err(format!("cannot connect to {}", config.url))
+///     }
+/// }
+///
+/// let container = ServiceProvider::new()
+///     .add_instance(Config { url: "db" })
+///     .add_singleton::<Result<Pool, String>>();
+///
+/// let error = container.init_singletons().unwrap_err();
+/// assert_eq!(error.failures[0].message, "cannot connect to db");
+/// ```
+pub trait TryDependency<Deps>: Sized {
+    type Error;
+
+    fn try_init(deps: Deps) -> Result<Self, Self::Error>;
+}
+
+impl<Deps, T> Dependency<Deps> for Result<T, T::Error>
+where
+    T: TryDependency<Deps>,
+    T::Error: Display,
+{
+    fn init(deps: Deps) -> Self {
+        T::try_init(deps)
+    }
+
+    fn init_failure(&self) -> Option<String> {
+        self.as_ref().err().map(ToString::to_string)
+    }
+}
+
+/// Trait is used for choosing a constructor of the service when registering it using
+/// `ServiceProvider::add_transient_with`. Markers implementing this trait are generated by the
+/// `#[inject]` macro for each method marked by `#[inject::init]` when there are more than one such
+/// method in the impl. `DefaultConstructor` calls `Dependency::init`.
+pub trait Constructor<T, Deps> {
+    fn construct(deps: Deps) -> T;
+
+    /// Same as `Dependency::init_failure`. Returns `None` by default.
+    fn init_failure(_service: &T) -> Option<String> {
+        None
+    }
+}
+
+/// Constructor that creates a service using `Dependency` trait. It is used by default in
+/// `ServiceProvider::add_transient`.
+#[derive(Debug)]
+pub struct DefaultConstructor;
+
+impl<T, Deps> Constructor<T, Deps> for DefaultConstructor
+where
+    T: Dependency<Deps>,
+{
+    fn construct(deps: Deps) -> T {
+        T::init(deps)
+    }
+
+    fn init_failure(service: &T) -> Option<String> {
+        service.init_failure()
+    }
 }
 
 impl<Deps, D> Dependency<Deps> for Rc<D>
@@ -43,6 +183,60 @@ where
         Arc::new(D::init(deps))
     }
 }
+#[cfg(feature = "std")]
+impl<Deps, D> Dependency<Deps> for Mutex<D>
+where
+    D: Dependency<Deps>,
+{
+    fn init(deps: Deps) -> Self {
+        Mutex::new(D::init(deps))
+    }
+}
+#[cfg(feature = "std")]
+impl<Deps, D> Dependency<Deps> for RwLock<D>
+where
+    D: Dependency<Deps>,
+{
+    fn init(deps: Deps) -> Self {
+        RwLock::new(D::init(deps))
+    }
+}
+#[cfg(feature = "parking-lot-support")]
+impl<Deps, D> Dependency<Deps> for parking_lot::Mutex<D>
+where
+    D: Dependency<Deps>,
+{
+    fn init(deps: Deps) -> Self {
+        parking_lot::Mutex::new(D::init(deps))
+    }
+}
+#[cfg(feature = "parking-lot-support")]
+impl<Deps, D> Dependency<Deps> for parking_lot::RwLock<D>
+where
+    D: Dependency<Deps>,
+{
+    fn init(deps: Deps) -> Self {
+        parking_lot::RwLock::new(D::init(deps))
+    }
+}
+#[cfg(feature = "tokio-support")]
+impl<Deps, D> Dependency<Deps> for tokio::sync::Mutex<D>
+where
+    D: Dependency<Deps>,
+{
+    fn init(deps: Deps) -> Self {
+        tokio::sync::Mutex::new(D::init(deps))
+    }
+}
+#[cfg(feature = "tokio-support")]
+impl<Deps, D> Dependency<Deps> for tokio::sync::RwLock<D>
+where
+    D: Dependency<Deps>,
+{
+    fn init(deps: Deps) -> Self {
+        tokio::sync::RwLock::new(D::init(deps))
+    }
+}
 
 /// Trait is used to resolve services by cloning. It must be implement only for wrappers that
 /// guarantees that there are only one instance and many references, like `Rc`, `Arc` structs and
@@ -54,3 +248,36 @@ impl<D> DependencyClone for Rc<D> {}
 impl<D> DependencyClone for Arc<D> {}
 
 impl<D> DependencyClone for &D {}
+
+/// Trait is used to convert pointers to the implementation into pointers to the trait object `I`
+/// (like `Box<Impl>` into `Box<dyn Trait>`). Generic coercion is not possible in stable Rust, so it
+/// is generated by `#[inject(provides = dyn Trait)]` attribute, but you can implement it yourself.
+///
+/// It is used by `ServiceProvider::add_transient_as` and `ServiceProvider::add_singleton_as`
+/// methods.
+pub trait Provides<I: ?Sized> {
+    fn provide_box(self: Box<Self>) -> Box<I>;
+    fn provide_rc(self: Rc<Self>) -> Rc<I>;
+    fn provide_arc(self: Arc<Self>) -> Arc<I>;
+}
+
+/// Trait is used to register a generic service for all type arguments at once using
+/// `ServiceProvider::add_generic::<Family>()`. `Family` is any marker type that names the set of
+/// services, like `Repositories` for `Repository<T>`. It is generated by
+/// `#[inject(family = Family)]` attribute, but you can implement it yourself:
+/// ```
+/// use teloc::*;
+///
+/// struct Repositories;
+/// struct Repository<T>(T);
+///
+/// impl<T: Default> Dependency<reexport::HList![]> for Repository<T> {
+///     fn init(_: reexport::HList![]) -> Self { Repository(T::default()) }
+/// }
+/// impl<T: Default> FamilyMember for Repository<T> {
+///     type Family = Repositories;
+/// }
+/// ```
+pub trait FamilyMember {
+    type Family;
+}
@@ -0,0 +1,257 @@
+use crate::container::{
+    ConvertContainer, ForwardContainer, GenericContainer, InstanceContainer, InstanceMutContainer,
+    LocatorContainer, ProvideContainer, SingletonContainer, TransientContainer,
+};
+use crate::locator::Locator;
+use crate::service_provider::{EmptyServiceProvider, ServiceProvider};
+use alloc::boxed::Box;
+use alloc::rc::Rc;
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::any::type_name;
+use frunk::{HCons, HNil};
+
+/// Lifetime of the registered service.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ServiceLifetime {
+    Transient,
+    Singleton,
+    Instance,
+    /// Service is resolved by forwarding to another registered service. See
+    /// `ServiceProvider::add_forward`.
+    Forward,
+}
+
+/// Description of one registered service returned by `ServiceProvider::describe`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ServiceDescriptor {
+    /// Name of the type by which the service is resolved.
+    pub type_name: &'static str,
+    pub lifetime: ServiceLifetime,
+    /// Name of the type from which the service is converted, if it is.
+    pub converts_from: Option<&'static str>,
+    /// Is the instance of the service already created. Always `false` for `Transient` and
+    /// `Forward` services and `true` for `Instance` services.
+    pub initialised: bool,
+}
+
+/// Trait is used to describe a container. Implement it for your container if you wish that your
+/// container can be described by `ServiceProvider::describe`.
+pub trait DescribeContainer {
+    fn describe_container(&self) -> ServiceDescriptor;
+}
+
+/// Trait is used to describe a list of containers and parents of `ServiceProvider`. Do not use it
+/// by yourself.
+pub trait Describe {
+    fn describe_into(&self, descriptors: &mut Vec<ServiceDescriptor>);
+}
+
+impl<Parent, Conts> ServiceProvider<Parent, Conts>
+where
+    Self: Describe,
+{
+    /// Returns descriptors of all services registered in the `ServiceProvider`, starting from the
+    /// last registered one, and then descriptors of services from parents.
+    ///
+    /// Usage:
+    /// ```
+    /// use teloc::*;
+    ///
+    /// struct Service;
+    /// #[inject]
+    /// impl Service {
+    ///     fn new() -> Self { Service }
+    /// }
+    ///
+    /// let container = ServiceProvider::new()
+    ///     .add_singleton::<Service>()
+    ///     .add_instance(10u8);
+    /// let scope = container.fork().add_transient::<Service>();
+    ///
+    /// let lifetimes = scope
+    ///     .describe()
+    ///     .into_iter()
+    ///     .map(|d| d.lifetime)
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(
+    ///     lifetimes,
+    ///     [ServiceLifetime::Transient, ServiceLifetime::Instance, ServiceLifetime::Singleton]
+    /// );
+    /// ```
+    pub fn describe(&self) -> Vec<ServiceDescriptor> {
+        let mut descriptors = vec![];
+        self.describe_into(&mut descriptors);
+        descriptors
+    }
+}
+
+impl<Parent, Conts> Describe for ServiceProvider<Parent, Conts>
+where
+    Parent: Describe,
+    Conts: Describe,
+{
+    fn describe_into(&self, descriptors: &mut Vec<ServiceDescriptor>) {
+        self.containers.describe_into(descriptors);
+        self.parent.describe_into(descriptors);
+    }
+}
+
+impl Describe for EmptyServiceProvider {
+    fn describe_into(&self, _: &mut Vec<ServiceDescriptor>) {}
+}
+
+impl<SP: Describe> Describe for &SP {
+    fn describe_into(&self, descriptors: &mut Vec<ServiceDescriptor>) {
+        (**self).describe_into(descriptors)
+    }
+}
+
+impl<SP: Describe> Describe for Rc<SP> {
+    fn describe_into(&self, descriptors: &mut Vec<ServiceDescriptor>) {
+        (**self).describe_into(descriptors)
+    }
+}
+
+impl<SP: Describe> Describe for Arc<SP> {
+    fn describe_into(&self, descriptors: &mut Vec<ServiceDescriptor>) {
+        (**self).describe_into(descriptors)
+    }
+}
+
+impl Describe for HNil {
+    fn describe_into(&self, _: &mut Vec<ServiceDescriptor>) {}
+}
+
+impl<H, T> Describe for HCons<H, T>
+where
+    H: DescribeContainer,
+    T: Describe,
+{
+    fn describe_into(&self, descriptors: &mut Vec<ServiceDescriptor>) {
+        descriptors.push(self.head.describe_container());
+        self.tail.describe_into(descriptors);
+    }
+}
+
+impl<T, Ctor> DescribeContainer for TransientContainer<T, Ctor> {
+    fn describe_container(&self) -> ServiceDescriptor {
+        ServiceDescriptor {
+            type_name: type_name::<T>(),
+            lifetime: ServiceLifetime::Transient,
+            converts_from: None,
+            initialised: false,
+        }
+    }
+}
+
+impl<T, Ctor> DescribeContainer for SingletonContainer<T, Ctor> {
+    fn describe_container(&self) -> ServiceDescriptor {
+        ServiceDescriptor {
+            type_name: type_name::<T>(),
+            lifetime: ServiceLifetime::Singleton,
+            converts_from: None,
+            initialised: self.get().get().is_some(),
+        }
+    }
+}
+
+impl<F> DescribeContainer for GenericContainer<F> {
+    fn describe_container(&self) -> ServiceDescriptor {
+        ServiceDescriptor {
+            type_name: type_name::<F>(),
+            lifetime: ServiceLifetime::Singleton,
+            converts_from: None,
+            initialised: !self.is_empty(),
+        }
+    }
+}
+
+impl DescribeContainer for LocatorContainer {
+    fn describe_container(&self) -> ServiceDescriptor {
+        ServiceDescriptor {
+            type_name: type_name::<dyn Locator>(),
+            lifetime: ServiceLifetime::Instance,
+            converts_from: None,
+            initialised: true,
+        }
+    }
+}
+
+impl<T> DescribeContainer for InstanceContainer<T> {
+    fn describe_container(&self) -> ServiceDescriptor {
+        ServiceDescriptor {
+            type_name: type_name::<T>(),
+            lifetime: ServiceLifetime::Instance,
+            converts_from: None,
+            initialised: true,
+        }
+    }
+}
+
+impl<T> DescribeContainer for InstanceMutContainer<T> {
+    fn describe_container(&self) -> ServiceDescriptor {
+        ServiceDescriptor {
+            type_name: type_name::<T>(),
+            lifetime: ServiceLifetime::Instance,
+            converts_from: None,
+            initialised: true,
+        }
+    }
+}
+
+impl<Cont, T, U> DescribeContainer for ConvertContainer<Cont, T, U>
+where
+    Cont: DescribeContainer,
+{
+    fn describe_container(&self) -> ServiceDescriptor {
+        ServiceDescriptor {
+            type_name: type_name::<U>(),
+            converts_from: Some(type_name::<T>()),
+            ..self.get().describe_container()
+        }
+    }
+}
+
+impl<T, Ctor, I: ?Sized> DescribeContainer for ProvideContainer<TransientContainer<T, Ctor>, T, I> {
+    fn describe_container(&self) -> ServiceDescriptor {
+        ServiceDescriptor {
+            type_name: type_name::<Box<I>>(),
+            converts_from: Some(type_name::<T>()),
+            ..self.get().describe_container()
+        }
+    }
+}
+
+impl<T, I: ?Sized> DescribeContainer for ProvideContainer<SingletonContainer<Arc<T>>, Arc<T>, I> {
+    fn describe_container(&self) -> ServiceDescriptor {
+        ServiceDescriptor {
+            type_name: type_name::<Arc<I>>(),
+            converts_from: Some(type_name::<Arc<T>>()),
+            ..self.get().describe_container()
+        }
+    }
+}
+
+impl<T, I: ?Sized> DescribeContainer for ForwardContainer<Arc<T>, I> {
+    fn describe_container(&self) -> ServiceDescriptor {
+        ServiceDescriptor {
+            type_name: type_name::<Arc<I>>(),
+            lifetime: ServiceLifetime::Forward,
+            converts_from: Some(type_name::<Arc<T>>()),
+            initialised: false,
+        }
+    }
+}
+
+impl<T, I: ?Sized> DescribeContainer for ForwardContainer<Rc<T>, I> {
+    fn describe_container(&self) -> ServiceDescriptor {
+        ServiceDescriptor {
+            type_name: type_name::<Rc<I>>(),
+            lifetime: ServiceLifetime::Forward,
+            converts_from: Some(type_name::<Rc<T>>()),
+            initialised: false,
+        }
+    }
+}
@@ -6,9 +6,37 @@ pub trait GetDependencies<'a, Deps, Indexes> {
     fn get_deps(&'a self) -> Deps;
 }
 
+/// Marker that is used as `Cont` param of `Resolver` when several services are resolved at once
+/// as a tuple or an `HList`. Do not use it by yourself.
+///
+/// Example:
+/// ```
+/// use teloc::*;
+///
+/// struct Config { name: &'static str }
+///
+/// struct Mailer { from: &'static str }
+/// #[inject]
+/// impl Mailer {
+///     fn new(config: &Config) -> Self { Mailer { from: config.name } }
+/// }
+///
+/// let sp = ServiceProvider::new()
+///     .add_instance(Config { name: "teloc" })
+///     .add_transient::<Mailer>();
+///
+/// let (config, mailer): (&Config, Mailer) = sp.resolve();
+/// assert_eq!(config.name, mailer.from);
+///
+/// let reexport::hlist_pat![mailer, config]: reexport::HList![Mailer, &Config] = sp.resolve();
+/// assert_eq!(config.name, mailer.from);
+/// ```
+pub struct Dependencies;
+
 mod impls {
-    use crate::get_dependencies::GetDependencies;
+    use crate::get_dependencies::{Dependencies, GetDependencies};
     use crate::resolver::Resolver;
+    use crate::service_provider::ServiceProvider;
     use frunk::hlist::HList;
     use frunk::{HCons, HNil};
 
@@ -27,4 +55,34 @@ mod impls {
             HNil
         }
     }
+
+    // Implemented only for `ServiceProvider` and not for any `SP`, otherwise method `resolve`
+    // would be found on wrappers like `AxumScope` without dereferencing them to the provider.
+    impl<'a, T, TRest, Infers, Parent, Conts> Resolver<'a, Dependencies, HCons<T, TRest>, Infers>
+        for ServiceProvider<Parent, Conts>
+    where
+        Self: GetDependencies<'a, HCons<T, TRest>, Infers>,
+    {
+        fn resolve(&'a self) -> HCons<T, TRest> {
+            self.get_deps()
+        }
+    }
+
+    macro_rules! impl_resolve_tuple {
+        ($($t:ident),+) => {
+            impl<'a, $($t,)+ Infers, Parent, Conts>
+                Resolver<'a, Dependencies, ($($t,)+), Infers> for ServiceProvider<Parent, Conts>
+            where
+                Self: GetDependencies<'a, frunk::HList![$($t),+], Infers>,
+            {
+                #[allow(non_snake_case)]
+                fn resolve(&'a self) -> ($($t,)+) {
+                    let frunk::hlist_pat![$($t),+] = self.get_deps();
+                    ($($t,)+)
+                }
+            }
+        };
+    }
+
+    for_each_arity!(impl_resolve_tuple);
 }
@@ -0,0 +1,191 @@
+use crate::container::{
+    GenericContainer, InstanceContainer, LocatorContainer, SingletonContainer, TransientContainer,
+};
+use crate::service_provider::{EmptyServiceProvider, ServiceProvider};
+use core::mem::ManuallyDrop;
+use frunk::{HCons, HNil};
+
+/// Builder of `ServiceProvider` that can be used in const context, for example to initialise a
+/// `static`. It has the same `add_*` methods as `ServiceProvider` for containers that can be
+/// created in const context. Create it by `ServiceProvider::const_builder` and finish by `build`.
+///
+/// Containers are stored in `ManuallyDrop`, because a generic value cannot be dropped in const
+/// context, so they are leaked if the builder is dropped without calling `build`.
+///
+/// Usage:
+/// ```
+/// use teloc::*;
+///
+/// struct Config { name: &'static str }
+///
+/// struct Service { name: &'static str }
+/// #[inject]
+/// impl Service {
+///     fn new(config: &Config) -> Self { Service { name: config.name } }
+/// }
+///
+/// static PROVIDER: ServiceProvider<
+///     EmptyServiceProvider,
+///     reexport::HList![TransientContainer<Service>, InstanceContainer<Config>],
+/// > = ServiceProvider::const_builder()
+///     .add_instance(Config { name: "teloc" })
+///     .add_transient::<Service>()
+///     .build();
+///
+/// let service: Service = PROVIDER.resolve();
+/// assert_eq!(service.name, "teloc");
+/// ```
+#[must_use]
+pub struct ConstBuilder<Conts>(ManuallyDrop<Conts>);
+
+impl ServiceProvider<EmptyServiceProvider, HNil> {
+    /// Creates a builder of `ServiceProvider` that can be used in const context. See
+    /// [`ConstBuilder`] for more information.
+    pub const fn const_builder() -> ConstBuilder<HNil> {
+        ConstBuilder(ManuallyDrop::new(HNil))
+    }
+}
+
+impl<Conts> ConstBuilder<Conts> {
+    const fn add<Cont>(self, container: Cont) -> ConstBuilder<HCons<Cont, Conts>> {
+        ConstBuilder(ManuallyDrop::new(HCons {
+            head: container,
+            tail: ManuallyDrop::into_inner(self.0),
+        }))
+    }
+
+    /// Same as `ServiceProvider::add_transient`.
+    pub const fn add_transient<T>(self) -> ConstBuilder<HCons<TransientContainer<T>, Conts>> {
+        self.add(TransientContainer::new())
+    }
+
+    /// Same as `ServiceProvider::add_transient_with`.
+    pub const fn add_transient_with<T, Ctor>(
+        self,
+    ) -> ConstBuilder<HCons<TransientContainer<T, Ctor>, Conts>> {
+        self.add(TransientContainer::new())
+    }
+
+    /// Same as `ServiceProvider::add_singleton`. Singletons are created lazily when they are
+    /// resolved first time.
+    pub const fn add_singleton<T>(self) -> ConstBuilder<HCons<SingletonContainer<T>, Conts>> {
+        self.add(SingletonContainer::new())
+    }
+
+    /// Same as `ServiceProvider::add_singleton_with`.
+    pub const fn add_singleton_with<T, Ctor>(
+        self,
+    ) -> ConstBuilder<HCons<SingletonContainer<T, Ctor>, Conts>> {
+        self.add(SingletonContainer::new())
+    }
+
+    /// Same as `ServiceProvider::add_generic`.
+    pub const fn add_generic<F>(self) -> ConstBuilder<HCons<GenericContainer<F>, Conts>> {
+        self.add(GenericContainer::new())
+    }
+
+    /// Same as `ServiceProvider::add_instance`. The instance must be created in const context.
+    pub const fn add_instance<T>(
+        self,
+        data: T,
+    ) -> ConstBuilder<HCons<InstanceContainer<T>, Conts>> {
+        self.add(InstanceContainer::new(data))
+    }
+
+    /// Same as `ServiceProvider::add_locator`.
+    pub const fn add_locator(self) -> ConstBuilder<HCons<LocatorContainer, Conts>> {
+        self.add(LocatorContainer::new())
+    }
+
+    /// Creates `ServiceProvider` with all added containers.
+    pub const fn build(self) -> ServiceProvider<EmptyServiceProvider, Conts> {
+        ServiceProvider {
+            parent: EmptyServiceProvider,
+            containers: ManuallyDrop::into_inner(self.0),
+        }
+    }
+}
+
+/// Service that can be resolved from a global `ServiceProvider` declared by [`global_provider!`].
+/// It is implemented by the macro for a hidden marker type of the provider for each service listed
+/// in the macro. Do not implement it by yourself.
+pub trait ResolveGlobal<T> {
+    fn resolve_global() -> T;
+}
+
+/// Declares a global `ServiceProvider` in a `static` and a function that resolves services from
+/// it, so the provider does not need to be passed around. Containers are listed in the same order
+/// as in the `HList` type of the provider, i.e. the last added container is the first one. Use
+/// [`ConstBuilder`] to create the provider.
+///
+/// Services that can be resolved by the function are listed after its name, so the type of the
+/// service can be specified as `resolve_global::<Service>()`, or it can be inferred. Lifetimes of
+/// references must be `'static`. Other services can be resolved from the `static` directly using
+/// `Resolver` trait, for example transient services which dependencies are added to forks of the
+/// provider only.
+///
+/// The function name is also used as a name of a hidden module of the provider, so several global
+/// providers can be declared in one module when their functions have different names.
+///
+/// Usage:
+/// ```
+/// use std::sync::atomic::{AtomicU32, Ordering};
+/// use teloc::*;
+///
+/// struct Counter(AtomicU32);
+/// #[inject]
+/// impl Counter {
+///     fn new() -> Self { Counter(0.into()) }
+/// }
+///
+/// global_provider! {
+///     static PROVIDER: [SingletonContainer<Counter>] =
+///         ServiceProvider::const_builder().add_singleton::<Counter>().build();
+///     fn resolve_global() -> [&'static Counter];
+/// }
+///
+/// fn increment() {
+///     let counter: &Counter = resolve_global();
+///     counter.0.fetch_add(1, Ordering::SeqCst);
+/// }
+///
+/// increment();
+/// increment();
+/// assert_eq!(resolve_global::<&Counter>().0.load(Ordering::SeqCst), 2);
+/// ```
+#[macro_export]
+macro_rules! global_provider {
+    (
+        $(#[$meta:meta])*
+        $vis:vis static $name:ident: [$($cont:ty),* $(,)?] = $init:expr;
+        $fn_vis:vis fn $fn_name:ident() -> [$($service:ty),* $(,)?];
+    ) => {
+        $(#[$meta])*
+        $vis static $name: $crate::ServiceProvider<
+            $crate::EmptyServiceProvider,
+            $crate::reexport::HList![$($cont),*],
+        > = $init;
+
+        #[doc(hidden)]
+        $fn_vis mod $fn_name {
+            pub struct Provider;
+        }
+
+        $(
+            impl $crate::ResolveGlobal<$service> for $fn_name::Provider {
+                fn resolve_global() -> $service {
+                    $crate::Resolver::resolve(&$name)
+                }
+            }
+        )*
+
+        /// Resolves a service from the global `ServiceProvider`.
+        #[allow(dead_code)]
+        $fn_vis fn $fn_name<T>() -> T
+        where
+            $fn_name::Provider: $crate::ResolveGlobal<T>,
+        {
+            <$fn_name::Provider as $crate::ResolveGlobal<T>>::resolve_global()
+        }
+    };
+}
@@ -0,0 +1,349 @@
+use crate::container::InstanceContainer;
+use crate::{GetDependencies, ServiceProvider};
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::sync::Arc;
+use core::future::Future;
+use core::pin::Pin;
+use frunk::{hlist, hlist_pat, HCons, HList, HNil};
+
+/// Function which arguments can be resolved from the provider of services `SP`. It is implemented
+/// for all functions and closures with up to 12 arguments. `Args` and `Infers` are inferred by the
+/// compiler, do not specify them by yourself.
+///
+/// Example:
+/// ```
+/// use teloc::*;
+///
+/// struct Number(u8);
+/// #[inject]
+/// impl Number {
+///     fn new() -> Self { Number(5) }
+/// }
+///
+/// fn double(number: Number) -> u8 { number.0 * 2 }
+///
+/// let sp = ServiceProvider::new().add_transient::<Number>();
+/// assert_eq!(double.call_with(&sp), 10);
+/// ```
+pub trait ResolveArgs<'a, SP, Args, Infers> {
+    type Output;
+
+    /// Resolves arguments of the function from `sp` and calls it.
+    fn call_with(self, sp: &'a SP) -> Self::Output;
+}
+
+macro_rules! impl_resolve_args {
+    ($($arg:ident),*) => {
+        impl<'a, SP, F, R, $($arg,)* Infers> ResolveArgs<'a, SP, ($($arg,)*), Infers> for F
+        where
+            F: FnOnce($($arg),*) -> R,
+            SP: GetDependencies<'a, HList![$($arg),*], Infers>,
+        {
+            type Output = R;
+
+            #[allow(non_snake_case)]
+            fn call_with(self, sp: &'a SP) -> R {
+                let hlist_pat![$($arg),*] = sp.get_deps();
+                (self)($($arg),*)
+            }
+        }
+    };
+}
+
+impl_resolve_args!();
+for_each_arity!(impl_resolve_args);
+
+/// Handler that takes runtime input `Input` and dependencies `Deps` resolved from the provider.
+/// It is generated by `#[handler]` macro for async functions, where the first arg of the function
+/// is the input and the other args are the dependencies. Called by `ServiceProvider::invoke`.
+///
+/// Handlers with different dependencies implement different `Invoke` traits, so they cannot be
+/// stored behind one trait object. Use [`HandlerRouter`] to dispatch inputs to handlers by name.
+pub trait Invoke<Input, Deps> {
+    type Output;
+    type Future: Future<Output = Self::Output>;
+
+    /// Calls the handler with the input and the dependencies.
+    fn invoke(&self, input: Input, deps: Deps) -> Self::Future;
+}
+
+impl<Parent, Conts> ServiceProvider<Parent, Conts> {
+    /// Resolves arguments of the function or the closure `f` from the `ServiceProvider` and calls
+    /// it. Functions with up to 12 arguments are supported. Types of arguments of closures must be
+    /// specified explicitly.
+    ///
+    /// Usage:
+    /// ```
+    /// use teloc::*;
+    ///
+    /// struct Repo { users: Vec<&'static str> }
+    ///
+    /// #[derive(Clone)]
+    /// struct Config { limit: usize }
+    ///
+    /// let sp = ServiceProvider::new()
+    ///     .add_instance(Repo { users: vec!["Alice", "Bob"] })
+    ///     .add_instance(Config { limit: 1 });
+    ///
+    /// let users = sp.call(|repo: &Repo, cfg: &Config| repo.users.len().min(cfg.limit));
+    /// assert_eq!(users, 1);
+    /// ```
+    pub fn call<'a, F, Args, Infers>(&'a self, f: F) -> F::Output
+    where
+        F: ResolveArgs<'a, Self, Args, Infers>,
+    {
+        f.call_with(self)
+    }
+
+    /// Same as `ServiceProvider::call`, but for async functions and closures that return a future.
+    /// Returns the future, arguments are resolved before it is returned.
+    ///
+    /// Usage:
+    /// ```
+    /// use teloc::*;
+    ///
+    /// struct Repo { users: Vec<&'static str> }
+    ///
+    /// async fn count(repo: &Repo) -> usize { repo.users.len() }
+    ///
+    /// async fn run() {
+    ///     let sp = ServiceProvider::new().add_instance(Repo { users: vec!["Alice"] });
+    ///
+    ///     assert_eq!(sp.call_async(count).await, 1);
+    ///     assert_eq!(sp.call_async(async |repo: &Repo| repo.users[0]).await, "Alice");
+    /// }
+    /// ```
+    pub fn call_async<'a, F, Args, Infers>(&'a self, f: F) -> F::Output
+    where
+        F: ResolveArgs<'a, Self, Args, Infers>,
+        F::Output: Future,
+    {
+        f.call_with(self)
+    }
+
+    /// Resolves dependencies of the `handler` from the `ServiceProvider` and calls it with the
+    /// `input`. Usually it is called on a scope forked for the input. See [`Invoke`] for more
+    /// information.
+    pub fn invoke<'a, H, Input, Deps, Infers>(&'a self, handler: &H, input: Input) -> H::Future
+    where
+        H: Invoke<Input, Deps>,
+        Self: GetDependencies<'a, Deps, Infers>,
+    {
+        handler.invoke(input, self.get_deps())
+    }
+}
+
+/// Future returned by a [`HandlerRoute`].
+pub type RouteFuture<'a, Output> = Pin<Box<dyn Future<Output = Output> + Send + 'a>>;
+
+/// Function that invokes a handler with the input on the provider of services `SP`. Usually it is
+/// a closure that calls `ServiceProvider::invoke`, for example
+/// `|sp, input| sp.invoke(&Handler, input)`.
+pub type HandlerRoute<SP, Input, Output> = for<'a> fn(&'a SP, Input) -> RouteFuture<'a, Output>;
+
+/// Router that dispatches inputs to handlers by name, for example messages from a queue by their
+/// topic. All handlers take the same `Input`, return the same `Output` and are invoked on the same
+/// provider of services `SP`, but can have different dependencies. Futures of the handlers must be
+/// `Send`.
+///
+/// Example:
+/// ```
+/// use teloc::*;
+///
+/// struct Order(u32);
+///
+/// struct Repo { discount: u32 }
+///
+/// #[handler]
+/// async fn process(order: Order, repo: &Repo) -> u32 { order.0 - repo.discount }
+///
+/// #[handler]
+/// async fn refund(order: Order) -> u32 { order.0 }
+///
+/// type SP = ServiceProvider<EmptyServiceProvider, reexport::HList![InstanceContainer<Repo>]>;
+///
+/// async fn dispatch() {
+///     let mut router: HandlerRouter<SP, Order, u32> = HandlerRouter::new();
+///     router
+///         .route("process", |sp, order| sp.invoke(&Process, order))
+///         .route("refund", |sp, order| sp.invoke(&Refund, order));
+///
+///     let sp = ServiceProvider::new().add_instance(Repo { discount: 10 });
+///     assert_eq!(router.dispatch(&sp, "process", Order(100)).unwrap().await, 90);
+///     assert_eq!(router.dispatch(&sp, "refund", Order(100)).unwrap().await, 100);
+///     assert!(router.dispatch(&sp, "cancel", Order(100)).is_none());
+/// }
+/// ```
+pub struct HandlerRouter<SP, Input, Output> {
+    routes: BTreeMap<&'static str, HandlerRoute<SP, Input, Output>>,
+}
+
+impl<SP, Input, Output> HandlerRouter<SP, Input, Output> {
+    /// Creates an empty router.
+    pub fn new() -> Self {
+        HandlerRouter {
+            routes: BTreeMap::new(),
+        }
+    }
+
+    /// Registers `route` by `name`. The previous route with the same name is replaced.
+    pub fn route(
+        &mut self,
+        name: &'static str,
+        route: HandlerRoute<SP, Input, Output>,
+    ) -> &mut Self {
+        self.routes.insert(name, route);
+        self
+    }
+
+    /// Invokes the handler registered by `name` with the `input` on `sp`. Returns `None` if there
+    /// is no handler with this name.
+    pub fn dispatch<'a>(
+        &self,
+        sp: &'a SP,
+        name: &str,
+        input: Input,
+    ) -> Option<RouteFuture<'a, Output>> {
+        self.routes.get(name).map(|route| route(sp, input))
+    }
+}
+
+impl<SP, Input, Output> Default for HandlerRouter<SP, Input, Output> {
+    fn default() -> Self {
+        HandlerRouter::new()
+    }
+}
+
+impl<SP, Input, Output> Clone for HandlerRouter<SP, Input, Output> {
+    fn clone(&self) -> Self {
+        HandlerRouter {
+            routes: self.routes.clone(),
+        }
+    }
+}
+
+/// List of values that are added as instances to a scope created by [`ScopedHandler`] or by
+/// integrations with web frameworks. Implemented for `HList`s of any values.
+pub trait Instances {
+    /// `HList` of `InstanceContainer`s of the values.
+    type Containers;
+
+    fn into_containers(self) -> Self::Containers;
+}
+
+impl Instances for HNil {
+    type Containers = HNil;
+
+    fn into_containers(self) -> HNil {
+        HNil
+    }
+}
+
+impl<H, T> Instances for HCons<H, T>
+where
+    T: Instances,
+{
+    type Containers = HCons<InstanceContainer<H>, T::Containers>;
+
+    fn into_containers(self) -> Self::Containers {
+        HCons {
+            head: InstanceContainer::new(self.head),
+            tail: self.tail.into_containers(),
+        }
+    }
+}
+
+/// Type of the `ServiceProvider` that is created by [`ScopedHandler`] for each invocation. It is a
+/// fork of the global `ServiceProvider` with values of the `HList` `I` added as instances.
+pub type HandlerScope<SP, I> = ServiceProvider<Arc<SP>, <I as Instances>::Containers>;
+
+/// Forks `sp` and adds `instances` to the fork. Used also by integrations with web frameworks.
+pub(crate) fn new_scope<SP, I>(sp: Arc<SP>, instances: I) -> HandlerScope<SP, I>
+where
+    I: Instances,
+{
+    ServiceProvider {
+        parent: sp,
+        containers: instances.into_containers(),
+    }
+}
+
+/// Handler that is independent of any framework and can be used for message consumers, cron jobs
+/// and so on. For each invocation a new scope is created by forking the global `ServiceProvider`
+/// and adding the input as an instance, then arguments of the handler function are resolved from
+/// the scope and the function is called.
+///
+/// One invocation is done in two steps: [`ScopedHandler::scope`] creates the scope and
+/// [`ScopedHandler::call`] calls the handler with it. They cannot be merged into one method,
+/// because arguments of the handler can borrow the scope, so the scope must outlive the future
+/// returned by the handler. Keep the scope in a local variable next to the call, then it is
+/// dropped right after the invocation. Both methods take `&self`, so one handler can process
+/// several inputs concurrently.
+///
+/// Example:
+/// ```
+/// use std::sync::Arc;
+/// use teloc::*;
+///
+/// struct Message(String);
+///
+/// struct Greeter<'a> { message: &'a Message }
+/// #[inject]
+/// impl<'a> Greeter<'a> {
+///     fn new(message: &'a Message) -> Self { Self { message } }
+/// }
+///
+/// async fn handle(greeter: Greeter<'_>) -> String {
+///     format!("Hello, {}", greeter.message.0)
+/// }
+///
+/// async fn consume() {
+///     let sp = Arc::new(ServiceProvider::new().add_transient::<Greeter>());
+///     let handler = ScopedHandler::new(sp, handle);
+///
+///     let scope = handler.scope(Message("world".to_string()));
+///     assert_eq!(handler.call(&scope).await, "Hello, world");
+/// }
+/// ```
+pub struct ScopedHandler<SP, F> {
+    sp: Arc<SP>,
+    f: F,
+}
+
+impl<SP, F> ScopedHandler<SP, F> {
+    /// Creates `ScopedHandler` with specified global `ServiceProvider` and handler function.
+    pub fn new(sp: Arc<SP>, f: F) -> Self {
+        ScopedHandler { sp, f }
+    }
+
+    /// Creates a new scope for one invocation by forking the global `ServiceProvider` and adding
+    /// `input` as an instance. Other instances can be added to the returned scope using
+    /// `add_instance` method.
+    pub fn scope<I>(&self, input: I) -> HandlerScope<SP, HList![I]> {
+        new_scope(self.sp.clone(), hlist![input])
+    }
+
+    /// Resolves arguments of the handler function from `scope` and calls it.
+    pub fn call<'a, S, Args, Infers>(
+        &'a self,
+        scope: &'a S,
+    ) -> <&'a F as ResolveArgs<'a, S, Args, Infers>>::Output
+    where
+        &'a F: ResolveArgs<'a, S, Args, Infers>,
+    {
+        (&self.f).call_with(scope)
+    }
+}
+
+impl<SP, F> Clone for ScopedHandler<SP, F>
+where
+    F: Clone,
+{
+    fn clone(&self) -> Self {
+        ScopedHandler {
+            sp: self.sp.clone(),
+            f: self.f.clone(),
+        }
+    }
+}
@@ -0,0 +1,328 @@
+use crate::container::{
+    ConvertContainer, ForwardContainer, GenericContainer, InstanceContainer, InstanceMutContainer,
+    LocatorContainer, ProvideContainer, SingletonContainer, TransientContainer,
+};
+use crate::dependency::Constructor;
+use crate::get_dependencies::GetDependencies;
+use crate::ServiceProvider;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::any::type_name;
+use core::fmt;
+use core::future::Future;
+use core::marker::PhantomData;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use frunk::{HCons, HNil};
+
+/// Singleton which constructor panicked or which value is an error (see `TryDependency`) while
+/// calling `ServiceProvider::init_singletons`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SingletonInitFailure {
+    pub type_name: &'static str,
+    /// Message of the panic or of the error.
+    pub message: String,
+}
+
+/// Error returned by `ServiceProvider::init_singletons` that contains all singletons which
+/// constructors panicked or which values are errors. Panics are collected only when they unwind,
+/// so with `panic = "abort"` profile setting or without `std` feature the first panic aborts the
+/// process and the error contains only errors returned by `TryDependency` constructors.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InitSingletonsError {
+    pub failures: Vec<SingletonInitFailure>,
+}
+
+impl fmt::Display for InitSingletonsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "failed to initialise {} singleton(s):",
+            self.failures.len()
+        )?;
+        for failure in &self.failures {
+            write!(f, "\n- {}: {}", failure.type_name, failure.message)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InitSingletonsError {}
+
+/// Trait is used to eagerly initialise a container. Implement it for your container if you wish
+/// that your container can be used in `ServiceProvider::init_singletons`.
+pub trait InitContainer<'a, SP, Infer> {
+    fn init_container(&'a self, sp: &'a SP) -> Result<(), SingletonInitFailure>;
+}
+
+/// Trait is used to eagerly initialise a list of containers. Do not use it by yourself.
+pub trait InitContainers<'a, SP, Infers> {
+    /// Initialises containers starting from `skip`-th one. Returns index of the next container if
+    /// containers after it are left uninitialised.
+    fn init_containers(
+        &'a self,
+        sp: &'a SP,
+        skip: usize,
+        step: bool,
+        failures: &mut Vec<SingletonInitFailure>,
+    ) -> Option<usize>;
+}
+
+impl<'a, SP> InitContainers<'a, SP, HNil> for HNil {
+    fn init_containers(
+        &'a self,
+        _: &'a SP,
+        _: usize,
+        _: bool,
+        _: &mut Vec<SingletonInitFailure>,
+    ) -> Option<usize> {
+        None
+    }
+}
+
+impl<'a, SP, H, T, HInfer, TInfer> InitContainers<'a, SP, HCons<HInfer, TInfer>> for HCons<H, T>
+where
+    H: InitContainer<'a, SP, HInfer>,
+    T: InitContainers<'a, SP, TInfer>,
+{
+    fn init_containers(
+        &'a self,
+        sp: &'a SP,
+        skip: usize,
+        step: bool,
+        failures: &mut Vec<SingletonInitFailure>,
+    ) -> Option<usize> {
+        if skip > 0 {
+            return self
+                .tail
+                .init_containers(sp, skip - 1, step, failures)
+                .map(|next| next + 1);
+        }
+        if let Err(failure) = self.head.init_container(sp) {
+            failures.push(failure);
+        }
+        if step {
+            Some(1)
+        } else {
+            self.tail
+                .init_containers(sp, 0, step, failures)
+                .map(|next| next + 1)
+        }
+    }
+}
+
+impl<Parent, Conts> ServiceProvider<Parent, Conts> {
+    /// Creates instances of all singletons registered in the `ServiceProvider` (but not in its
+    /// parents), so constructors will be called at startup rather than when the service is
+    /// requested first time. All singletons which values are errors are returned in the error, see
+    /// [`TryDependency`] for fallible constructors. This is the preferred way to report failures.
+    ///
+    /// With `std` feature panics in constructors are caught by `catch_unwind` and are returned in
+    /// the error too, but they are still reported by the panic hook, which prints them to stderr
+    /// by default. Panics are not caught without `std` feature, and with `panic = "abort"`
+    /// profile setting the process is aborted on the first panic, so nothing is returned.
+    ///
+    /// [`TryDependency`]: crate::TryDependency
+    ///
+    /// Usage:
+    /// ```
+    /// use teloc::*;
+    ///
+    /// struct Pool;
+    /// #[inject]
+    /// impl Pool {
+    ///     fn new() -> Self { Pool }
+    /// }
+    ///
+    /// let container = ServiceProvider::new().add_singleton::<Pool>();
+    /// assert!(!container.describe()[0].initialised);
+    ///
+    /// container.init_singletons().unwrap();
+    /// assert!(container.describe()[0].initialised);
+    ///
+    /// # #[cfg(feature = "std")] {
+    /// struct Broken;
+    /// #[inject]
+    /// impl Broken {
+    ///     fn new() -> Self { panic!("cannot connect") }
+    /// }
+    ///
+    /// let container = ServiceProvider::new()
+    ///     .add_singleton::<Pool>()
+    ///     .add_singleton::<Broken>();
+    /// let error = container.init_singletons().unwrap_err();
+    ///
+    /// assert_eq!(error.failures.len(), 1);
+    /// assert_eq!(error.failures[0].message, "cannot connect");
+    /// assert!(container.describe()[1].initialised);
+    /// # }
+    /// ```
+    pub fn init_singletons<'a, Infers>(&'a self) -> Result<(), InitSingletonsError>
+    where
+        Conts: InitContainers<'a, Self, Infers>,
+    {
+        let mut failures = vec![];
+        self.containers
+            .init_containers(self, 0, false, &mut failures);
+        into_result(failures)
+    }
+
+    /// Same as `ServiceProvider::init_singletons`, but returns a future that yields to the
+    /// executor after initialising of each container, so startup does not block other tasks for
+    /// a long time.
+    pub fn init_singletons_async<'a, Infers>(
+        &'a self,
+    ) -> impl Future<Output = Result<(), InitSingletonsError>> + 'a
+    where
+        Conts: InitContainers<'a, Self, Infers>,
+        Infers: 'a,
+    {
+        InitSingletonsFuture {
+            sp: self,
+            next: Some(0),
+            failures: vec![],
+            infers: PhantomData,
+        }
+    }
+}
+
+struct InitSingletonsFuture<'a, Parent, Conts, Infers> {
+    sp: &'a ServiceProvider<Parent, Conts>,
+    next: Option<usize>,
+    failures: Vec<SingletonInitFailure>,
+    infers: PhantomData<fn() -> Infers>,
+}
+
+impl<'a, Parent, Conts, Infers> Future for InitSingletonsFuture<'a, Parent, Conts, Infers>
+where
+    Conts: InitContainers<'a, ServiceProvider<Parent, Conts>, Infers>,
+{
+    type Output = Result<(), InitSingletonsError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        match this.next {
+            Some(skip) => {
+                let sp = this.sp;
+                this.next = sp
+                    .containers
+                    .init_containers(sp, skip, true, &mut this.failures);
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+            None => Poll::Ready(into_result(core::mem::take(&mut this.failures))),
+        }
+    }
+}
+
+fn into_result(failures: Vec<SingletonInitFailure>) -> Result<(), InitSingletonsError> {
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(InitSingletonsError { failures })
+    }
+}
+
+/// Calls `f` and catches a panic in it if `std` feature is enabled.
+#[cfg(feature = "std")]
+fn catch_panic<F: FnOnce()>(type_name: &'static str, f: F) -> Result<(), SingletonInitFailure> {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    catch_unwind(AssertUnwindSafe(f)).map_err(|payload| {
+        let message = if let Some(message) = payload.downcast_ref::<&str>() {
+            String::from(*message)
+        } else if let Some(message) = payload.downcast_ref::<String>() {
+            message.clone()
+        } else {
+            String::from("constructor panicked")
+        };
+        SingletonInitFailure { type_name, message }
+    })
+}
+
+/// Calls `f` and catches a panic in it if `std` feature is enabled.
+#[cfg(not(feature = "std"))]
+fn catch_panic<F: FnOnce()>(_: &'static str, f: F) -> Result<(), SingletonInitFailure> {
+    f();
+    Ok(())
+}
+
+impl<'a, T, Ctor, SP, Deps, Infer> InitContainer<'a, SP, (Deps, Infer)>
+    for SingletonContainer<T, Ctor>
+where
+    SP: GetDependencies<'a, Deps, Infer>,
+    Ctor: Constructor<T, Deps>,
+{
+    fn init_container(&'a self, sp: &'a SP) -> Result<(), SingletonInitFailure> {
+        let mut failure = None;
+        catch_panic(type_name::<T>(), || {
+            let service = self.get().get_or_init(|| Ctor::construct(sp.get_deps()));
+            failure = Ctor::init_failure(service);
+        })?;
+        match failure {
+            Some(message) => Err(SingletonInitFailure {
+                type_name: type_name::<T>(),
+                message,
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<'a, T, Ctor, SP> InitContainer<'a, SP, ()> for TransientContainer<T, Ctor> {
+    fn init_container(&'a self, _: &'a SP) -> Result<(), SingletonInitFailure> {
+        Ok(())
+    }
+}
+
+// Members of the family are not known until they are resolved.
+impl<'a, F, SP> InitContainer<'a, SP, ()> for GenericContainer<F> {
+    fn init_container(&'a self, _: &'a SP) -> Result<(), SingletonInitFailure> {
+        Ok(())
+    }
+}
+
+impl<'a, T, SP> InitContainer<'a, SP, ()> for InstanceContainer<T> {
+    fn init_container(&'a self, _: &'a SP) -> Result<(), SingletonInitFailure> {
+        Ok(())
+    }
+}
+
+impl<'a, T, SP> InitContainer<'a, SP, ()> for InstanceMutContainer<T> {
+    fn init_container(&'a self, _: &'a SP) -> Result<(), SingletonInitFailure> {
+        Ok(())
+    }
+}
+
+impl<'a, SP> InitContainer<'a, SP, ()> for LocatorContainer {
+    fn init_container(&'a self, _: &'a SP) -> Result<(), SingletonInitFailure> {
+        Ok(())
+    }
+}
+
+impl<'a, P, I: ?Sized, SP> InitContainer<'a, SP, ()> for ForwardContainer<P, I> {
+    fn init_container(&'a self, _: &'a SP) -> Result<(), SingletonInitFailure> {
+        Ok(())
+    }
+}
+
+impl<'a, Cont, T, U, SP, Infer> InitContainer<'a, SP, Infer> for ConvertContainer<Cont, T, U>
+where
+    Cont: InitContainer<'a, SP, Infer>,
+{
+    fn init_container(&'a self, sp: &'a SP) -> Result<(), SingletonInitFailure> {
+        self.get().init_container(sp)
+    }
+}
+
+impl<'a, Cont, T, I: ?Sized, SP, Infer> InitContainer<'a, SP, Infer>
+    for ProvideContainer<Cont, T, I>
+where
+    Cont: InitContainer<'a, SP, Infer>,
+{
+    fn init_container(&'a self, sp: &'a SP) -> Result<(), SingletonInitFailure> {
+        self.get().init_container(sp)
+    }
+}
@@ -5,7 +5,7 @@
 //! There are four lifetimes for dependencies:
 //! 1. `Transient`. Service will be created when resolves. Can depend on dependencies with anything lifetime.
 //! 2. `Singleton`. Service will be created once at `ServiceProvider` when it resolved (lazy). Can depend on dependencies
-//! with anything lifetime. Cannot depend on services from forked `ServiceProvider` instances.
+//!    with anything lifetime. Cannot depend on services from forked `ServiceProvider` instances.
 //! 3. `Instance`. Dependency was created outside of `ServiceProvider` and can be used by any other dependency.
 //!
 //! How to work:
@@ -17,6 +17,12 @@
 //! 6. Get service from provider using `.resolve()` method.
 //! 7. Work with service.
 //!
+//! teloc can be used in `no_std` environments with `alloc` when default `std` feature is disabled.
+//! Singletons then use `once_cell` with `critical-section` feature, so an implementation of
+//! `critical-section` must be provided by the target, and other containers use spin locks from
+//! `spin` crate. Panics in constructors are not caught by `ServiceProvider::init_singletons`
+//! without `std`.
+//!
 //! Example:
 //! ```rust
 //! use std::rc::Rc;
@@ -56,30 +62,65 @@
 //! assert_eq!(*controller.number_service.number, 10);
 //! ```
 
-#![deny(unsafe_code)]
+#![forbid(unsafe_code)]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[macro_use]
+mod macros;
 
 #[cfg(feature = "actix-support")]
 mod actix_support;
+#[cfg(feature = "axum-support")]
+mod axum_support;
 mod container;
 mod dependency;
+mod describe;
 mod get_dependencies;
+mod global_provider;
+mod handler;
 mod index;
+mod init_singletons;
+mod locator;
 mod resolver;
 mod service_provider;
+mod sync;
+#[cfg(feature = "tonic-support")]
+mod tonic_support;
 
 #[cfg(feature = "actix-support")]
-pub use actix_support::DiActixHandler;
+pub use actix_support::{ActixInject, ActixInjector, ActixRequestScope, ActixScope};
+#[cfg(feature = "axum-support")]
+pub use axum_support::{AxumInject, AxumRequestScope, AxumScope, AxumScopeLayer, AxumScopeService};
+#[cfg(feature = "tonic-support")]
+pub use tonic_support::{TonicRequestScope, TonicScope, TonicScopeInterceptor};
 
 pub use {
     container::*,
-    dependency::{Dependency, DependencyClone},
-    get_dependencies::GetDependencies,
+    dependency::{
+        Constructor, DefaultConstructor, Dependency, DependencyClone, FamilyMember, Provides,
+        TryDependency,
+    },
+    describe::{Describe, DescribeContainer, ServiceDescriptor, ServiceLifetime},
+    get_dependencies::{Dependencies, GetDependencies},
+    global_provider::{ConstBuilder, ResolveGlobal},
+    handler::{
+        HandlerRoute, HandlerRouter, HandlerScope, Instances, Invoke, ResolveArgs, RouteFuture,
+        ScopedHandler,
+    },
+    init_singletons::{InitContainer, InitContainers, InitSingletonsError, SingletonInitFailure},
+    locator::Locator,
     resolver::Resolver,
-    service_provider::{EmptyServiceProvider, SelectContainer, ServiceProvider},
-    teloc_macros::{inject, Dependency},
+    service_provider::{
+        ContainerRegisteredOnce, EmptyServiceProvider, SelectContainer, SelectEach,
+        ServiceProvider, UniqueContainers,
+    },
+    teloc_macros::{handler, inject, Dependency},
 };
 
 #[doc(hidden)]
 pub mod reexport {
-    pub use {frunk, frunk::HList};
+    pub extern crate alloc;
+    pub use {frunk, frunk::hlist_pat, frunk::HList};
 }
@@ -0,0 +1,104 @@
+use crate::service_provider::ServiceProvider;
+use core::any::Any;
+
+/// Type-erased `ServiceProvider`. When [`LocatorContainer`] is added to the provider by
+/// `ServiceProvider::add_locator`, `&dyn Locator` can be resolved from any provider which type is
+/// `'static`, pointing at the provider that resolves it. Services can depend on it without naming
+/// the type of the provider, and get the provider back by `downcast_ref` when they need to resolve
+/// something from it, for example a plugin chosen by name at runtime.
+///
+/// Scopes created by `fork` borrow the parent, so their type is not `'static`. Use `fork_rc` or
+/// `fork_arc` to create scopes from which `Locator` can be resolved:
+/// ```compile_fail
+/// use teloc::*;
+///
+/// let sp = ServiceProvider::new().add_instance(5u8);
+/// let scope = sp.fork().add_locator();
+/// let locator: &dyn Locator = scope.resolve();
+/// ```
+/// ```
+/// use std::rc::Rc;
+/// use teloc::*;
+///
+/// let sp = Rc::new(ServiceProvider::new().add_instance(5u8));
+/// let scope = sp.fork_rc().add_locator();
+/// let locator: &dyn Locator = scope.resolve();
+/// assert!(std::ptr::eq(locator.downcast_ref().unwrap(), &scope));
+/// ```
+///
+/// Example:
+/// ```
+/// use teloc::*;
+///
+/// trait Plugin { fn name(&self) -> &str; }
+///
+/// struct Markdown;
+/// impl Plugin for Markdown { fn name(&self) -> &str { "markdown" } }
+/// #[inject]
+/// impl Markdown {
+///     fn new() -> Self { Markdown }
+/// }
+///
+/// struct Html;
+/// impl Plugin for Html { fn name(&self) -> &str { "html" } }
+/// #[inject]
+/// impl Html {
+///     fn new() -> Self { Html }
+/// }
+///
+/// struct Config { plugin: &'static str }
+///
+/// struct Renderer { plugin: Box<dyn Plugin> }
+/// #[inject]
+/// impl Renderer {
+///     fn new(config: &Config, locator: &dyn Locator) -> Self {
+///         let sp = locator.downcast_ref::<AppProvider>().unwrap();
+///         let plugin: Box<dyn Plugin> = if config.plugin == "markdown" {
+///             let markdown: Markdown = sp.resolve();
+///             Box::new(markdown)
+///         } else {
+///             let html: Html = sp.resolve();
+///             Box::new(html)
+///         };
+///         Renderer { plugin }
+///     }
+/// }
+///
+/// type AppProvider = ServiceProvider<
+///     EmptyServiceProvider,
+///     reexport::HList![
+///         TransientContainer<Renderer>,
+///         InstanceContainer<Config>,
+///         TransientContainer<Html>,
+///         TransientContainer<Markdown>,
+///         LocatorContainer,
+///     ],
+/// >;
+///
+/// let sp: AppProvider = ServiceProvider::new()
+///     .add_locator()
+///     .add_transient::<Markdown>()
+///     .add_transient::<Html>()
+///     .add_instance(Config { plugin: "markdown" })
+///     .add_transient::<Renderer>();
+///
+/// let renderer: Renderer = sp.resolve();
+/// assert_eq!(renderer.plugin.name(), "markdown");
+/// ```
+pub trait Locator {
+    /// Returns the provider as `&dyn Any`. Use `downcast_ref` instead of calling it directly.
+    fn as_any(&self) -> &dyn Any;
+}
+
+impl dyn Locator + '_ {
+    /// Returns the provider if it is of type `SP`.
+    pub fn downcast_ref<SP: 'static>(&self) -> Option<&SP> {
+        self.as_any().downcast_ref()
+    }
+}
+
+impl<Parent: 'static, Conts: 'static> Locator for ServiceProvider<Parent, Conts> {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
@@ -0,0 +1,18 @@
+/// Calls macro `$m` for every list of type params `A1, .., An` where `n` is from 1 to 12. Used for
+/// implementing traits for tuples and functions of different arity.
+macro_rules! for_each_arity {
+    ($m:ident) => {
+        $m!(A1);
+        $m!(A1, A2);
+        $m!(A1, A2, A3);
+        $m!(A1, A2, A3, A4);
+        $m!(A1, A2, A3, A4, A5);
+        $m!(A1, A2, A3, A4, A5, A6);
+        $m!(A1, A2, A3, A4, A5, A6, A7);
+        $m!(A1, A2, A3, A4, A5, A6, A7, A8);
+        $m!(A1, A2, A3, A4, A5, A6, A7, A8, A9);
+        $m!(A1, A2, A3, A4, A5, A6, A7, A8, A9, A10);
+        $m!(A1, A2, A3, A4, A5, A6, A7, A8, A9, A10, A11);
+        $m!(A1, A2, A3, A4, A5, A6, A7, A8, A9, A10, A11, A12);
+    };
+}
@@ -3,7 +3,8 @@
 /// only when you implement your own version of container.
 ///
 /// For common usage you need only import it from teloc, and calling `resolve` method when you need
-/// to get a service from `ServiceProvider`.
+/// to get a service from `ServiceProvider`. Several services can be resolved at once as a tuple
+/// (up to 12 elements) or as an `HList`, for example `let (a, b): (A, &B) = sp.resolve();`.
 ///
 /// Example:
 ///
@@ -1,11 +1,16 @@
 use crate::container::{
-    Container, ConvertContainer, InstanceContainer, SingletonContainer, TransientContainer,
+    Container, ConvertContainer, ForwardContainer, GenericContainer, InstanceContainer,
+    InstanceMutContainer, LocatorContainer, ProvideContainer, SingletonContainer,
+    TransientContainer,
 };
+use crate::dependency::Provides;
 use crate::index::{ParentIndex, SelfIndex};
+use alloc::rc::Rc;
+use alloc::sync::Arc;
+use core::ops::Add;
 use frunk::hlist::{HList, Selector};
+use frunk::indices::{Here, There};
 use frunk::{HCons, HNil};
-use std::rc::Rc;
-use std::sync::Arc;
 
 /// `ServiceProvider` struct is used as an IoC-container in which you declare your dependencies.
 ///
@@ -59,7 +64,7 @@ pub struct EmptyServiceProvider;
 
 impl ServiceProvider<EmptyServiceProvider, HNil> {
     /// Create an empty instance of `ServiceProvider`
-    pub fn new() -> Self {
+    pub const fn new() -> Self {
         ServiceProvider {
             parent: EmptyServiceProvider,
             containers: HNil,
@@ -80,7 +85,7 @@ impl<Parent, Conts> ServiceProvider<Parent, Conts> {
 
     /// Forking `ServiceProvider` creates a new `ServiceProvider` with reference to the parent.
     /// `resolve` method on forked `ServiceProvider` will find dependencies form self and parent.
-    pub fn fork(&self) -> ServiceProvider<&Self, HNil> {
+    pub const fn fork(&self) -> ServiceProvider<&Self, HNil> {
         ServiceProvider {
             parent: self,
             containers: HNil,
@@ -115,6 +120,10 @@ type ContainerSingletonAddConvert<Parent, T, U, Conts> =
     ServiceProvider<Parent, HCons<ConvertContainer<SingletonContainer<T>, T, U>, Conts>>;
 type ContainerInstanceAddConvert<Parent, T, U, Conts> =
     ServiceProvider<Parent, HCons<ConvertContainer<InstanceContainer<T>, T, U>, Conts>>;
+type ContainerTransientAddProvide<Parent, T, I, Conts> =
+    ServiceProvider<Parent, HCons<ProvideContainer<TransientContainer<T>, T, I>, Conts>>;
+type ContainerSingletonAddProvide<Parent, T, I, Conts> =
+    ServiceProvider<Parent, HCons<ProvideContainer<SingletonContainer<Arc<T>>, Arc<T>, I>, Conts>>;
 
 impl<Parent, Conts: HList> ServiceProvider<Parent, Conts> {
     /// Method used primary for internal actions. In common usage you don't need to use it. It add dependencies to the store. You need
@@ -173,6 +182,46 @@ impl<Parent, Conts: HList> ServiceProvider<Parent, Conts> {
         self._add::<TransientContainer<T>>(())
     }
 
+    /// Same as `ServiceProvider::add_transient`, but service will be created using the specified
+    /// constructor. Markers of constructors are generated by `#[inject]` macro for each method
+    /// marked by `#[inject::init]` when there are more than one such method. Name of a marker is the
+    /// name of the type followed by the name of the method in `CamelCase`, or it can be specified
+    /// as `#[inject::init(Name)]`. The first of such methods is used by
+    /// `ServiceProvider::add_transient`.
+    ///
+    /// Usage:
+    /// ```
+    /// use teloc::*;
+    ///
+    /// struct Clock { time: u64 }
+    /// #[inject]
+    /// impl Clock {
+    ///     #[inject::init]
+    ///     pub fn new() -> Self { Self { time: 1_600_000_000 } }
+    ///     #[inject::init]
+    ///     pub fn with_mock_clock(time: &u64) -> Self { Self { time: *time } }
+    /// }
+    ///
+    /// let sp = ServiceProvider::new()
+    ///     .add_instance(0u64)
+    ///     .add_transient_with::<Clock, ClockWithMockClock>();
+    /// let clock: Clock = sp.resolve();
+    /// assert_eq!(clock.time, 0);
+    ///
+    /// let sp = ServiceProvider::new()
+    ///     .add_transient::<Clock>();
+    /// let clock: Clock = sp.resolve();
+    /// assert_eq!(clock.time, 1_600_000_000);
+    /// ```
+    pub fn add_transient_with<T, Ctor>(
+        self,
+    ) -> ServiceProvider<Parent, HCons<TransientContainer<T, Ctor>, Conts>>
+    where
+        TransientContainer<T, Ctor>: Container<Data = ()>,
+    {
+        self._add::<TransientContainer<T, Ctor>>(())
+    }
+
     /// Add dependency with the `Singleton` lifetime. Singleton services will be created only one
     /// time when it will be called first time. It will be same between different calls in parent
     /// and forked `ServiceProvider`
@@ -229,6 +278,81 @@ impl<Parent, Conts: HList> ServiceProvider<Parent, Conts> {
         self._add::<SingletonContainer<T>>(())
     }
 
+    /// Same as `ServiceProvider::add_singleton`, but service will be created using the specified
+    /// constructor. See `ServiceProvider::add_transient_with` for markers of constructors.
+    ///
+    /// Usage:
+    /// ```
+    /// use teloc::*;
+    ///
+    /// struct Clock { time: u64 }
+    /// #[inject]
+    /// impl Clock {
+    ///     #[inject::init]
+    ///     pub fn new() -> Self { Self { time: 1_600_000_000 } }
+    ///     #[inject::init]
+    ///     pub fn with_mock_clock(time: &u64) -> Self { Self { time: *time } }
+    /// }
+    ///
+    /// let sp = ServiceProvider::new()
+    ///     .add_instance(0u64)
+    ///     .add_singleton_with::<Clock, ClockWithMockClock>();
+    /// let clock: &Clock = sp.resolve();
+    /// assert_eq!(clock.time, 0);
+    /// ```
+    pub fn add_singleton_with<T, Ctor>(
+        self,
+    ) -> ServiceProvider<Parent, HCons<SingletonContainer<T, Ctor>, Conts>>
+    where
+        SingletonContainer<T, Ctor>: Container<Data = ()>,
+    {
+        self._add::<SingletonContainer<T, Ctor>>(())
+    }
+
+    /// Add all services which `FamilyMember::Family` is `F` with `Singleton` lifetime. Every
+    /// service of the family is created once when it is resolved first time, and is resolved as
+    /// `Arc<Service>`. Use it for generic services like `Repository<T>`, so you do not need to add
+    /// `Repository<User>`, `Repository<Order>` and so on one by one.
+    ///
+    /// Services must be `Send + Sync + 'static`.
+    ///
+    /// Usage:
+    /// ```
+    /// use std::sync::Arc;
+    /// use teloc::*;
+    ///
+    /// trait Entity: Send + Sync + 'static {
+    ///     const TABLE: &'static str;
+    /// }
+    /// struct User;
+    /// impl Entity for User { const TABLE: &'static str = "users"; }
+    /// struct Order;
+    /// impl Entity for Order { const TABLE: &'static str = "orders"; }
+    ///
+    /// struct Repositories;
+    /// struct Repository<T> { table: &'static str, entity: std::marker::PhantomData<T> }
+    /// #[inject(family = Repositories)]
+    /// impl<T: Entity> Repository<T> {
+    ///     fn new() -> Self { Repository { table: T::TABLE, entity: std::marker::PhantomData } }
+    /// }
+    ///
+    /// let sp = ServiceProvider::new().add_generic::<Repositories>();
+    ///
+    /// let users1: Arc<Repository<User>> = sp.resolve();
+    /// let users2: Arc<Repository<User>> = sp.resolve();
+    /// let orders: Arc<Repository<Order>> = sp.resolve();
+    ///
+    /// assert!(Arc::ptr_eq(&users1, &users2));
+    /// assert_eq!(users1.table, "users");
+    /// assert_eq!(orders.table, "orders");
+    /// ```
+    pub fn add_generic<F>(self) -> ServiceProvider<Parent, HCons<GenericContainer<F>, Conts>>
+    where
+        GenericContainer<F>: Container<Data = ()>,
+    {
+        self._add::<GenericContainer<F>>(())
+    }
+
     /// Add anything instance to provider. It likes singleton, but it cannot get dependencies from
     /// the provider. Use it for adding single objects like configs.
     ///
@@ -271,6 +395,75 @@ impl<Parent, Conts: HList> ServiceProvider<Parent, Conts> {
         self._add::<InstanceContainer<T>>(data)
     }
 
+    /// Same as `ServiceProvider::add_instance`, but the instance can be changed by dependencies.
+    /// It is resolved as [`InstanceMut`] guard that gives `&mut T`, or as `&RefCell<T>`. Borrows
+    /// are checked at runtime: resolving of `InstanceMut` while another guard of the same instance
+    /// is alive panics. Use it for a state of the local scope like a unit of work.
+    ///
+    /// Usage:
+    /// ```
+    /// use teloc::*;
+    ///
+    /// struct UnitOfWork { changes: Vec<&'static str> }
+    ///
+    /// struct Service<'a> { uow: InstanceMut<'a, UnitOfWork> }
+    /// #[inject]
+    /// impl<'a> Service<'a> {
+    ///     fn new(uow: InstanceMut<'a, UnitOfWork>) -> Self { Self { uow } }
+    /// }
+    ///
+    /// let sp = ServiceProvider::new()
+    ///     .add_instance_mut(UnitOfWork { changes: vec![] })
+    ///     .add_transient::<Service>();
+    ///
+    /// let mut service: Service = sp.resolve();
+    /// service.uow.changes.push("insert");
+    /// drop(service);
+    ///
+    /// let uow: InstanceMut<UnitOfWork> = sp.resolve();
+    /// assert_eq!(uow.changes, ["insert"]);
+    /// ```
+    ///
+    /// [`InstanceMut`]: crate::InstanceMut
+    pub fn add_instance_mut<T>(
+        self,
+        data: T,
+    ) -> ServiceProvider<Parent, HCons<InstanceMutContainer<T>, Conts>>
+    where
+        InstanceMutContainer<T>: Container<Data = T>,
+    {
+        self._add::<InstanceMutContainer<T>>(data)
+    }
+
+    /// Add `LocatorContainer`, so the provider that resolves a service can be resolved itself as
+    /// `&ServiceProvider<..>` or as type-erased `&dyn Locator`. See [`Locator`] for more
+    /// information.
+    ///
+    /// [`Locator`]: crate::Locator
+    ///
+    /// Resolving of references to generic types, like `&impl Trait` args of constructors, can
+    /// become ambiguous after adding the container, because the provider also can be resolved by
+    /// reference.
+    ///
+    /// Usage:
+    /// ```
+    /// use teloc::*;
+    ///
+    /// let sp = ServiceProvider::new().add_locator().add_instance(5u8);
+    ///
+    /// let number = sp.call(|sp: &ServiceProvider<_, _>| {
+    ///     let number: &u8 = sp.resolve();
+    ///     *number
+    /// });
+    /// assert_eq!(number, 5);
+    ///
+    /// let locator: &dyn Locator = sp.resolve();
+    /// assert!(std::ptr::eq(locator.downcast_ref().unwrap(), &sp));
+    /// ```
+    pub fn add_locator(self) -> ServiceProvider<Parent, HCons<LocatorContainer, Conts>> {
+        self._add::<LocatorContainer>(())
+    }
+
     /// Same as `ServiceProvider::add_transient`, but can be used for convert one type to another
     /// when resolving. Can be used for creating `Box<dyn Trait>` instances, for example.
     ///
@@ -347,6 +540,201 @@ impl<Parent, Conts: HList> ServiceProvider<Parent, Conts> {
     {
         self._add::<ConvertContainer<InstanceContainer<T>, T, U>>(instance)
     }
+
+    /// Add dependency `T` with the `Transient` lifetime that will be resolved as `Box<I>`, where
+    /// `I` is a trait object implemented by `T`. `T` must implement [`Provides<I>`] trait that can
+    /// be generated by `#[inject(provides = dyn Trait)]` attribute.
+    ///
+    /// Usage:
+    /// ```
+    /// use teloc::*;
+    ///
+    /// trait NumberService {
+    ///     fn get_num(&self) -> i32;
+    /// }
+    ///
+    /// struct TenService;
+    /// impl NumberService for TenService {
+    ///     fn get_num(&self) -> i32 {
+    ///         10
+    ///     }
+    /// }
+    /// #[inject(provides = dyn NumberService)]
+    /// impl TenService {
+    ///     fn new() -> Self {
+    ///         Self
+    ///     }
+    /// }
+    ///
+    /// let container = ServiceProvider::new()
+    ///     .add_transient_as::<dyn NumberService, TenService>();
+    /// let service: Box<dyn NumberService> = container.resolve();
+    ///
+    /// assert_eq!(service.get_num(), 10);
+    /// ```
+    ///
+    /// [`Provides<I>`]: crate::Provides
+    pub fn add_transient_as<I, T>(self) -> ContainerTransientAddProvide<Parent, T, I, Conts>
+    where
+        I: ?Sized,
+        T: Provides<I>,
+    {
+        self._add::<ProvideContainer<TransientContainer<T>, T, I>>(())
+    }
+
+    /// Same as `ServiceProvider::add_transient_as` but for `Singleton` lifetime. Service will be
+    /// resolved as `Arc<I>`.
+    pub fn add_singleton_as<I, T>(self) -> ContainerSingletonAddProvide<Parent, T, I, Conts>
+    where
+        I: ?Sized,
+        T: Provides<I>,
+    {
+        self._add::<ProvideContainer<SingletonContainer<Arc<T>>, Arc<T>, I>>(())
+    }
+
+    /// Add alias that resolves `Arc<I>` (or `Rc<I>`) by resolving the pointer `P` (`Arc<T>` or
+    /// `Rc<T>`) from the `ServiceProvider` and converting it using [`Provides<I>`] trait. Unlike
+    /// `ServiceProvider::add_singleton_as`, it does not store its own instance, so one singleton
+    /// can be exposed as several trait objects.
+    ///
+    /// Usage:
+    /// ```
+    /// use std::sync::Arc;
+    /// use teloc::*;
+    ///
+    /// trait Repo {
+    ///     fn get(&self) -> i32;
+    /// }
+    /// trait HealthCheck {
+    ///     fn healthy(&self) -> bool;
+    /// }
+    ///
+    /// struct Database;
+    /// impl Repo for Database {
+    ///     fn get(&self) -> i32 {
+    ///         10
+    ///     }
+    /// }
+    /// impl HealthCheck for Database {
+    ///     fn healthy(&self) -> bool {
+    ///         true
+    ///     }
+    /// }
+    /// #[inject(provides = dyn Repo, provides = dyn HealthCheck)]
+    /// impl Database {
+    ///     fn new() -> Self {
+    ///         Database
+    ///     }
+    /// }
+    ///
+    /// let container = ServiceProvider::new()
+    ///     .add_singleton::<Arc<Database>>()
+    ///     .add_forward::<dyn Repo, Arc<Database>>()
+    ///     .add_forward::<dyn HealthCheck, Arc<Database>>();
+    /// let repo: Arc<dyn Repo> = container.resolve();
+    /// let health: Arc<dyn HealthCheck> = container.resolve();
+    /// let database: Arc<Database> = container.resolve();
+    ///
+    /// assert_eq!(repo.get(), 10);
+    /// assert!(health.healthy());
+    /// assert_eq!(Arc::strong_count(&database), 4);
+    /// ```
+    ///
+    /// [`Provides<I>`]: crate::Provides
+    pub fn add_forward<I, P>(self) -> ServiceProvider<Parent, HCons<ForwardContainer<P, I>, Conts>>
+    where
+        I: ?Sized,
+    {
+        self._add::<ForwardContainer<P, I>>(())
+    }
+}
+
+impl<Parent, Conts> ServiceProvider<Parent, Conts> {
+    /// Merge two `ServiceProvider`s into one. Containers from `other` are appended to the
+    /// containers of `self`, parent of `self` is kept. `other` must not have a parent, so use this
+    /// method for combining modules that were built independently.
+    ///
+    /// Merged providers must not contain the same container twice, otherwise it would be
+    /// impossible to choose which of them must be used when resolving, so such code will not
+    /// compile.
+    ///
+    /// Usage:
+    /// ```
+    /// use teloc::*;
+    ///
+    /// struct Service { number: i32 }
+    /// #[inject]
+    /// impl Service {
+    ///     fn new(number: &i32) -> Self { Self { number: *number } }
+    /// }
+    ///
+    /// fn config_module() -> ServiceProvider<EmptyServiceProvider, reexport::HList![InstanceContainer<i32>]> {
+    ///     ServiceProvider::new().add_instance(10)
+    /// }
+    ///
+    /// let sp = ServiceProvider::new()
+    ///     .add_transient::<Service>()
+    ///     .merge(config_module());
+    ///
+    /// let service: Service = sp.resolve();
+    /// assert_eq!(service.number, 10);
+    /// ```
+    ///
+    /// Merging providers with the same container fails to compile:
+    /// ```compile_fail
+    /// use teloc::*;
+    ///
+    /// let sp = ServiceProvider::new()
+    ///     .add_instance(10i32)
+    ///     .merge(ServiceProvider::new().add_instance(20i32));
+    /// ```
+    pub fn merge<OtherConts, Indexes>(
+        self,
+        other: ServiceProvider<EmptyServiceProvider, OtherConts>,
+    ) -> ServiceProvider<Parent, <Conts as Add<OtherConts>>::Output>
+    where
+        Conts: Add<OtherConts>,
+        <Conts as Add<OtherConts>>::Output: UniqueContainers<Indexes>,
+    {
+        let ServiceProvider { parent, containers } = self;
+        ServiceProvider {
+            parent,
+            containers: containers + other.containers,
+        }
+    }
+}
+
+/// Trait that is implemented for lists of containers in which each container can be selected
+/// unambiguously, i.e. there are no duplicates in the list. Used for checking merged providers.
+pub trait UniqueContainers<Indexes> {}
+
+impl<Conts, Indexes> UniqueContainers<Indexes> for Conts where Conts: SelectEach<Conts, Indexes> {}
+
+/// Helper trait for `UniqueContainers` that selects each element of the list from `List`.
+pub trait SelectEach<List, Indexes> {}
+
+impl<List> SelectEach<List, HNil> for HNil {}
+
+impl<List, H, T, Index, IndexRest> SelectEach<List, HCons<Index, IndexRest>> for HCons<H, T>
+where
+    List: ContainerRegisteredOnce<H, Index>,
+    T: SelectEach<List, IndexRest>,
+{
+}
+
+/// Trait that is implemented for lists of containers which contain `Cont` at `Index`. It works like
+/// `Selector`, but when `Cont` is registered more than once, the `type annotations needed` error of
+/// `merge` names this trait and the duplicated container:
+/// ```text
+/// multiple `impl`s satisfying `HCons<..>: ContainerRegisteredOnce<InstanceContainer<i32>, _>` found
+/// ```
+pub trait ContainerRegisteredOnce<Cont, Index> {}
+
+impl<Cont, Tail> ContainerRegisteredOnce<Cont, Here> for HCons<Cont, Tail> {}
+
+impl<Cont, Head, Tail, Index> ContainerRegisteredOnce<Cont, There<Index>> for HCons<Head, Tail> where
+    Tail: ContainerRegisteredOnce<Cont, Index>
+{
 }
 
 /// Borrow containers from a ServiceProvider.
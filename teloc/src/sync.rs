@@ -0,0 +1,21 @@
+//! Synchronisation primitives that are used by containers. Mutex is taken from `std` with `std`
+//! feature, otherwise from `spin`. `OnceCell` uses `critical-section` without `std` feature.
+
+pub use once_cell::sync::OnceCell;
+#[cfg(feature = "std")]
+pub(crate) use std::sync::{Mutex, MutexGuard};
+
+#[cfg(feature = "std")]
+pub(crate) fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+#[cfg(not(feature = "std"))]
+pub(crate) use spin::{Mutex, MutexGuard};
+
+#[cfg(not(feature = "std"))]
+pub(crate) fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock()
+}
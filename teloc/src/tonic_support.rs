@@ -0,0 +1,118 @@
+//! Support for `tonic` crate.
+
+use crate::handler::{new_scope, HandlerScope};
+use frunk::{hlist, HList};
+use std::net::SocketAddr;
+use std::ops::Deref;
+use std::sync::Arc;
+use tonic::metadata::MetadataMap;
+use tonic::service::Interceptor;
+use tonic::{Request, Status};
+
+/// Type of the `ServiceProvider` that is created for each call. It is a fork of the global
+/// `ServiceProvider` with the request [`MetadataMap`] and the remote address of the call
+/// (`Option<SocketAddr>`) added as instances.
+pub type TonicRequestScope<SP> = HandlerScope<SP, HList![Option<SocketAddr>, MetadataMap]>;
+
+/// Local scope of a gRPC call. Dependencies are resolved from the scope inside of the service
+/// method using the `Resolver` trait, so they can borrow the scope and can be used across `.await`
+/// points.
+///
+/// Scope can be created by the [`TonicScopeInterceptor`] and got from the request using
+/// [`TonicScope::from_request`], or created directly in the service method using
+/// [`TonicScope::new`].
+///
+/// Example:
+/// ```
+/// use std::sync::Arc;
+/// use teloc::*;
+/// use tonic::metadata::MetadataMap;
+/// use tonic::{Request, Status};
+///
+/// struct Auth<'a> { metadata: &'a MetadataMap }
+/// #[inject]
+/// impl<'a> Auth<'a> {
+///     fn new(metadata: &'a MetadataMap) -> Self { Self { metadata } }
+/// }
+///
+/// type AppProvider = ServiceProvider<EmptyServiceProvider, reexport::HList![TransientContainer<Auth<'static>>]>;
+///
+/// async fn greet(request: Request<String>) -> Result<String, Status> {
+///     let scope = TonicScope::<AppProvider>::from_request(&request)?;
+///     let auth: Auth = scope.resolve();
+///     Ok(format!("{}, {:?}", request.get_ref(), auth.metadata.get("user")))
+/// }
+///
+/// let sp = Arc::new(ServiceProvider::new().add_transient::<Auth>());
+/// // Pass the interceptor to `tonic::service::interceptor::InterceptedService::new` or to the
+/// // `with_interceptor` method of a generated server.
+/// let interceptor = TonicScopeInterceptor::new(sp);
+/// ```
+pub struct TonicScope<SP>(Arc<TonicRequestScope<SP>>);
+
+impl<SP> TonicScope<SP> {
+    /// Creates a new scope by forking `sp` and adding metadata and remote address of the
+    /// `request` as instances.
+    pub fn new<T>(sp: Arc<SP>, request: &Request<T>) -> Self {
+        let instances = hlist![request.remote_addr(), request.metadata().clone()];
+        TonicScope(Arc::new(new_scope(sp, instances)))
+    }
+
+    /// Gets the scope created by the [`TonicScopeInterceptor`] for the `request`. Returns
+    /// `Status::internal` if the interceptor is not installed.
+    pub fn from_request<T>(request: &Request<T>) -> Result<Self, Status>
+    where
+        SP: Send + Sync + 'static,
+    {
+        request
+            .extensions()
+            .get::<TonicScope<SP>>()
+            .cloned()
+            .ok_or_else(|| Status::internal("TonicScopeInterceptor is not installed"))
+    }
+}
+
+impl<SP> Clone for TonicScope<SP> {
+    fn clone(&self) -> Self {
+        TonicScope(self.0.clone())
+    }
+}
+
+impl<SP> Deref for TonicScope<SP> {
+    type Target = TonicRequestScope<SP>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// `tonic` interceptor that creates a [`TonicScope`] for each call and stores it in the request
+/// extensions.
+pub struct TonicScopeInterceptor<SP> {
+    sp: Arc<SP>,
+}
+
+impl<SP> TonicScopeInterceptor<SP> {
+    pub fn new(sp: Arc<SP>) -> Self {
+        TonicScopeInterceptor { sp }
+    }
+}
+
+impl<SP> Clone for TonicScopeInterceptor<SP> {
+    fn clone(&self) -> Self {
+        TonicScopeInterceptor {
+            sp: self.sp.clone(),
+        }
+    }
+}
+
+impl<SP> Interceptor for TonicScopeInterceptor<SP>
+where
+    SP: Send + Sync + 'static,
+{
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        let scope = TonicScope::new(self.sp.clone(), &request);
+        request.extensions_mut().insert(scope);
+        Ok(request)
+    }
+}
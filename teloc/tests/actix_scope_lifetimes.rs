@@ -0,0 +1,107 @@
+//! These tests do not need an async runtime, so they can be run under miri:
+//! `cargo +nightly miri test --features actix-support --test actix_scope_lifetimes`.
+#![cfg(feature = "actix-support")]
+
+use actix_web::dev::Payload;
+use actix_web::http::Method;
+use actix_web::test::TestRequest;
+use actix_web::{web, FromRequest, HttpRequest};
+use std::sync::Arc;
+use teloc::*;
+
+struct Repository {
+    data: String,
+}
+#[inject]
+impl Repository {
+    fn new() -> Self {
+        Repository {
+            data: "previous".to_owned(),
+        }
+    }
+}
+
+// The same lifetimes as in `examples/actix_example`.
+struct Service<'a> {
+    store: Arc<Repository>,
+    method: &'a Method,
+}
+#[inject]
+impl<'a> Service<'a> {
+    fn new(store: Arc<Repository>, method: &'a Method) -> Self {
+        Self { store, method }
+    }
+}
+
+type AppProvider = ServiceProvider<
+    EmptyServiceProvider,
+    reexport::HList![
+        TransientContainer<Service<'static>>,
+        SingletonContainer<Arc<Repository>>,
+    ],
+>;
+
+fn provider() -> Arc<AppProvider> {
+    Arc::new(
+        ServiceProvider::new()
+            .add_singleton::<Arc<Repository>>()
+            .add_transient::<Service>(),
+    )
+}
+
+fn request(sp: &Arc<AppProvider>) -> HttpRequest {
+    TestRequest::post()
+        .app_data(web::Data::from(sp.clone()))
+        .to_http_request()
+}
+
+fn extract(req: &HttpRequest) -> ActixScope<AppProvider> {
+    ActixScope::from_request(req, &mut Payload::None)
+        .into_inner()
+        .unwrap()
+}
+
+#[test]
+fn test_scope_outlives_request() {
+    let sp = provider();
+    let scope = {
+        let req = request(&sp);
+        extract(&req)
+    };
+    let service: Service = scope.resolve();
+    assert_eq!(service.method, Method::POST);
+    assert_eq!(service.store.data, "previous");
+}
+
+#[test]
+fn test_scope_shared_between_extractors() {
+    let sp = provider();
+    let req = request(&sp);
+    let first = extract(&req);
+    let second = extract(&req);
+    let first_req: &HttpRequest = first.resolve();
+    let second_req: &HttpRequest = second.resolve();
+    assert!(std::ptr::eq(first_req, second_req));
+}
+
+#[test]
+fn test_scope_not_kept_by_request() {
+    let sp = provider();
+    let req = request(&sp);
+    let scope = extract(&req);
+    // `sp`, app data of the request and the scope.
+    assert_eq!(Arc::strong_count(&sp), 3);
+    drop(scope);
+    assert_eq!(Arc::strong_count(&sp), 2);
+}
+
+#[test]
+fn test_no_reference_cycle() {
+    let sp = provider();
+    {
+        let req = request(&sp);
+        let scope = extract(&req);
+        let _: Service = scope.resolve();
+    }
+    assert_eq!(Arc::strong_count(&sp), 1);
+}
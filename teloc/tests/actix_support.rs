@@ -0,0 +1,139 @@
+#![cfg(feature = "actix-support")]
+
+use actix_web::http::Method;
+use actix_web::{test, web, App};
+use std::sync::Arc;
+use teloc::*;
+
+struct Service<'a> {
+    method: &'a Method,
+}
+#[inject]
+impl<'a> Service<'a> {
+    fn new(method: &'a Method) -> Self {
+        Self { method }
+    }
+}
+
+struct Config {
+    prefix: &'static str,
+}
+
+struct Greeting {
+    text: String,
+}
+#[inject]
+impl Greeting {
+    fn new(config: &Arc<Config>, method: &Method) -> Self {
+        Self {
+            text: format!("{} {}", config.prefix, method),
+        }
+    }
+}
+
+type AppProvider = ServiceProvider<
+    EmptyServiceProvider,
+    reexport::HList![
+        TransientContainer<Greeting>,
+        InstanceContainer<Arc<Config>>,
+        TransientContainer<Service<'static>>
+    ],
+>;
+
+async fn handler(body: String, scope: ActixScope<AppProvider>) -> String {
+    let service: Service = scope.resolve();
+    format!("{} {}", service.method, body)
+}
+
+async fn same_scope(first: ActixScope<AppProvider>, second: ActixScope<AppProvider>) -> String {
+    let first: &actix_web::HttpRequest = first.resolve();
+    let second: &actix_web::HttpRequest = second.resolve();
+    assert!(std::ptr::eq(first, second));
+    "same".to_owned()
+}
+
+async fn inject(
+    id: web::Path<u32>,
+    ActixInject(config): ActixInject<Arc<Config>>,
+    greeting: ActixInject<Greeting>,
+) -> String {
+    format!("{}: {}{}", greeting.text, config.prefix, id)
+}
+
+fn provider() -> web::Data<AppProvider> {
+    web::Data::from(Arc::new(
+        ServiceProvider::new()
+            .add_transient::<Service>()
+            .add_instance(Arc::new(Config { prefix: "user" }))
+            .add_transient::<Greeting>(),
+    ))
+}
+
+#[actix_web::test]
+async fn test_extractor() {
+    let app = test::init_service(
+        App::new()
+            .app_data(provider())
+            .route("/", web::post().to(handler)),
+    )
+    .await;
+    let req = test::TestRequest::post()
+        .uri("/")
+        .set_payload("body")
+        .to_request();
+    let body = test::call_and_read_body(&app, req).await;
+    assert_eq!(body, "POST body");
+}
+
+#[actix_web::test]
+async fn test_extractors_share_scope() {
+    let app = test::init_service(
+        App::new()
+            .app_data(provider())
+            .route("/", web::get().to(same_scope)),
+    )
+    .await;
+    let req = test::TestRequest::get().uri("/").to_request();
+    let body = test::call_and_read_body(&app, req).await;
+    assert_eq!(body, "same");
+}
+
+#[actix_web::test]
+async fn test_missing_provider() {
+    let app = test::init_service(App::new().route("/", web::post().to(handler))).await;
+    let req = test::TestRequest::post().uri("/").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_server_error());
+}
+
+#[actix_web::test]
+async fn test_inject() {
+    let app = test::init_service(
+        App::new()
+            .app_data(provider())
+            .app_data(ActixInjector::new(
+                |scope: &ActixRequestScope<AppProvider>| -> Arc<Config> { scope.resolve() },
+            ))
+            .app_data(ActixInjector::new(
+                |scope: &ActixRequestScope<AppProvider>| -> Greeting { scope.resolve() },
+            ))
+            .route("/users/{id}", web::get().to(inject)),
+    )
+    .await;
+    let req = test::TestRequest::get().uri("/users/7").to_request();
+    let body = test::call_and_read_body(&app, req).await;
+    assert_eq!(body, "user GET: user7");
+}
+
+#[actix_web::test]
+async fn test_inject_unregistered() {
+    let app = test::init_service(
+        App::new()
+            .app_data(provider())
+            .route("/users/{id}", web::get().to(inject)),
+    )
+    .await;
+    let req = test::TestRequest::get().uri("/users/7").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_server_error());
+}
@@ -0,0 +1,51 @@
+use std::cell::RefCell;
+use teloc::*;
+
+#[derive(Default)]
+struct UnitOfWork {
+    changes: Vec<String>,
+}
+
+struct UserRepository<'a> {
+    uow: InstanceMut<'a, UnitOfWork>,
+}
+#[inject]
+impl<'a> UserRepository<'a> {
+    fn new(uow: InstanceMut<'a, UnitOfWork>) -> Self {
+        Self { uow }
+    }
+}
+impl UserRepository<'_> {
+    fn add(&mut self, name: &str) {
+        self.uow.changes.push(format!("insert {}", name));
+    }
+}
+
+#[test]
+fn test_add_instance_mut() {
+    let container = ServiceProvider::new().add_transient::<UserRepository>();
+    let scope = container.fork().add_instance_mut(UnitOfWork::default());
+
+    {
+        let mut repository: UserRepository = scope.resolve();
+        repository.add("Alice");
+    }
+    {
+        let mut repository: UserRepository = scope.resolve();
+        repository.add("Bob");
+    }
+
+    let uow: &RefCell<UnitOfWork> = scope.resolve();
+    assert_eq!(uow.borrow().changes, ["insert Alice", "insert Bob"]);
+}
+
+#[test]
+#[should_panic(expected = "is already borrowed")]
+fn test_add_instance_mut_double_borrow() {
+    let container = ServiceProvider::new()
+        .add_instance_mut(UnitOfWork::default())
+        .add_transient::<UserRepository>();
+
+    let _first: UserRepository = container.resolve();
+    let _second: UserRepository = container.resolve();
+}
@@ -0,0 +1,146 @@
+#![cfg(feature = "axum-support")]
+
+use axum::body::Body;
+use axum::extract::Path as UrlPath;
+use axum::http::request::Parts;
+use axum::http::{Request, StatusCode};
+use axum::routing::get;
+use axum::Router;
+use std::sync::Arc;
+use teloc::*;
+use tower::ServiceExt;
+
+struct Path<'a> {
+    path: &'a str,
+}
+#[inject]
+impl<'a> Path<'a> {
+    fn new(parts: &'a Parts) -> Self {
+        Self {
+            path: parts.uri.path(),
+        }
+    }
+}
+
+struct Config {
+    prefix: &'static str,
+}
+
+struct Greeting {
+    text: String,
+}
+#[inject]
+impl Greeting {
+    fn new(config: &Arc<Config>, parts: &Parts) -> Self {
+        Self {
+            text: format!("{} {}", config.prefix, parts.method),
+        }
+    }
+}
+
+type AppProvider = ServiceProvider<
+    EmptyServiceProvider,
+    reexport::HList![
+        TransientContainer<Greeting>,
+        InstanceContainer<Arc<Config>>,
+        TransientContainer<Path<'static>>
+    ],
+>;
+
+async fn handler(scope: AxumScope<AppProvider>) -> String {
+    let path: Path = scope.resolve();
+    path.path.to_owned()
+}
+
+async fn same_scope(first: AxumScope<AppProvider>, second: AxumScope<AppProvider>) -> String {
+    let first: &Parts = first.resolve();
+    let second: &Parts = second.resolve();
+    assert!(std::ptr::eq(first, second));
+    "same".to_owned()
+}
+
+async fn inject(
+    UrlPath(id): UrlPath<u32>,
+    AxumInject(config): AxumInject<Arc<Config>>,
+    greeting: AxumInject<Greeting>,
+) -> String {
+    format!("{}: {}{}", greeting.text, config.prefix, id)
+}
+
+async fn inject_unregistered(AxumInject(_): AxumInject<Greeting>) -> String {
+    unreachable!()
+}
+
+fn provider() -> Arc<AppProvider> {
+    Arc::new(
+        ServiceProvider::new()
+            .add_transient::<Path>()
+            .add_instance(Arc::new(Config { prefix: "user" }))
+            .add_transient::<Greeting>(),
+    )
+}
+
+async fn call(app: Router, uri: &str) -> String {
+    let response = app
+        .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    String::from_utf8(body.to_vec()).unwrap()
+}
+
+#[tokio::test]
+async fn test_extractor() {
+    let app = Router::new()
+        .route("/hello", get(handler))
+        .with_state(provider());
+    assert_eq!(call(app, "/hello").await, "/hello");
+}
+
+#[tokio::test]
+async fn test_extractors_share_scope() {
+    let app = Router::new()
+        .route("/", get(same_scope))
+        .with_state(provider());
+    assert_eq!(call(app, "/").await, "same");
+}
+
+#[tokio::test]
+async fn test_layer() {
+    let sp = provider();
+    let app = Router::new()
+        .route("/layer", get(handler))
+        .layer(AxumScopeLayer::new(sp.clone()))
+        .with_state(sp);
+    assert_eq!(call(app, "/layer").await, "/layer");
+}
+
+#[tokio::test]
+async fn test_inject() {
+    let sp = provider();
+    let layer = AxumScopeLayer::new(sp.clone())
+        .inject(|scope| -> Arc<Config> { scope.resolve() })
+        .inject(|scope| -> Greeting { scope.resolve() });
+    let app = Router::new()
+        .route("/users/{id}", get(inject))
+        .layer(layer)
+        .with_state(sp);
+    assert_eq!(call(app, "/users/7").await, "user GET: user7");
+}
+
+#[tokio::test]
+async fn test_inject_unregistered() {
+    let sp = provider();
+    let app = Router::new()
+        .route("/", get(inject_unregistered))
+        .layer(AxumScopeLayer::new(sp.clone()))
+        .with_state(sp);
+    let response = app
+        .oneshot(Request::get("/").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+}
@@ -0,0 +1,85 @@
+use std::rc::Rc;
+use teloc::*;
+
+struct Repo {
+    users: Vec<&'static str>,
+}
+#[inject]
+impl Repo {
+    fn new() -> Self {
+        Repo {
+            users: vec!["Alice", "Bob"],
+        }
+    }
+}
+
+struct Config {
+    prefix: &'static str,
+}
+
+#[test]
+fn test_call() {
+    let sp = ServiceProvider::new()
+        .add_singleton::<Repo>()
+        .add_instance(Rc::new(Config { prefix: "user" }));
+
+    let names = sp.call(|repo: &Repo, cfg: Rc<Config>| {
+        repo.users
+            .iter()
+            .map(|name| format!("{}:{}", cfg.prefix, name))
+            .collect::<Vec<_>>()
+    });
+    assert_eq!(names, ["user:Alice", "user:Bob"]);
+
+    assert_eq!(sp.call(|| 5), 5);
+}
+
+struct One(u16);
+#[inject]
+impl One {
+    fn new() -> Self {
+        One(1)
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn sum(
+    a: One,
+    b: One,
+    c: One,
+    d: One,
+    e: One,
+    f: One,
+    g: One,
+    h: One,
+    i: One,
+    j: One,
+    k: One,
+    l: &u16,
+) -> u16 {
+    [a, b, c, d, e, f, g, h, i, j, k]
+        .iter()
+        .map(|x| x.0)
+        .sum::<u16>()
+        + l
+}
+
+#[test]
+fn test_call_12_args() {
+    let sp = ServiceProvider::new()
+        .add_transient::<One>()
+        .add_instance(100u16);
+
+    assert_eq!(sp.call(sum), 111);
+}
+
+#[tokio::test]
+async fn test_call_async() {
+    let sp = ServiceProvider::new().add_transient::<Repo>();
+    let scope = sp.fork().add_instance(1usize);
+
+    let user = scope
+        .call_async(async |repo: Repo, index: &usize| repo.users[*index])
+        .await;
+    assert_eq!(user, "Bob");
+}
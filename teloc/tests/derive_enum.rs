@@ -0,0 +1,51 @@
+use teloc::{Dependency, Resolver, ServiceProvider};
+
+struct Number(u8);
+impl Number {
+    fn init(number: u8) -> Self {
+        Number(number)
+    }
+}
+
+#[derive(Dependency)]
+enum Storage {
+    #[allow(dead_code)]
+    Memory,
+    #[inject_variant]
+    File {
+        #[init(5)]
+        descriptor: Number,
+    },
+}
+
+#[derive(Dependency)]
+enum Mode {
+    #[default_variant]
+    Production,
+    #[allow(dead_code)]
+    Test(Number),
+}
+
+#[derive(Dependency)]
+enum App {
+    #[inject_variant]
+    Running(Storage, Mode),
+    #[allow(dead_code)]
+    Stopped,
+}
+
+#[test]
+fn test() {
+    let provider = ServiceProvider::new()
+        .add_transient::<Storage>()
+        .add_transient::<Mode>()
+        .add_transient::<App>();
+    let app: App = provider.resolve();
+
+    match app {
+        App::Running(Storage::File { descriptor }, Mode::Production) => {
+            assert_eq!(descriptor.0, 5)
+        }
+        _ => panic!("Wrong variant was constructed"),
+    }
+}
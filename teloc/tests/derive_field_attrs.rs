@@ -0,0 +1,106 @@
+use teloc::{inject, Dependency, Resolver, ServiceProvider};
+
+struct Config {
+    number: i32,
+}
+
+trait NumberService {
+    fn get_num(&self) -> i32;
+}
+
+struct ConstService {
+    number: i32,
+}
+impl NumberService for ConstService {
+    fn get_num(&self) -> i32 {
+        self.number
+    }
+}
+#[inject]
+impl ConstService {
+    fn new(config: &Config) -> Self {
+        ConstService {
+            number: config.number,
+        }
+    }
+}
+impl From<Box<ConstService>> for Box<dyn NumberService> {
+    fn from(x: Box<ConstService>) -> Self {
+        x
+    }
+}
+
+fn double(config: &Config, service: ConstService) -> i32 {
+    config.number + service.number
+}
+
+fn answer() -> i32 {
+    42
+}
+
+#[derive(Dependency)]
+struct Controller<'a> {
+    #[default]
+    counter: Vec<u8>,
+    #[init_with(double(&'a Config, ConstService))]
+    doubled: i32,
+    #[init_with(answer)]
+    answer: i32,
+    #[resolve(as = Box<ConstService>)]
+    number_service: Box<dyn NumberService>,
+    config: &'a Config,
+}
+
+fn a() -> i32 {
+    1
+}
+
+struct Number(i32);
+impl Number {
+    fn init(number: i32) -> Self {
+        Number(number)
+    }
+}
+
+// Fields are bound to generated names, they must not shadow `a` function
+#[derive(Dependency)]
+struct Shadowing<'a> {
+    config: &'a Config,
+    #[init(a())]
+    first: Number,
+    #[init_with(a)]
+    second: i32,
+}
+
+#[derive(Dependency)]
+struct Tuple(
+    #[default] u8,
+    #[resolve(as = Box<ConstService>)] Box<dyn NumberService>,
+);
+
+#[test]
+fn test() {
+    let config = Config { number: 10 };
+    let provider = ServiceProvider::new()
+        .add_instance(&config)
+        .add_transient::<ConstService>()
+        .add_transient::<Box<ConstService>>()
+        .add_transient::<Controller>()
+        .add_transient::<Tuple>()
+        .add_transient::<Shadowing>();
+
+    let controller: Controller = provider.resolve();
+    assert!(controller.counter.is_empty());
+    assert_eq!(controller.doubled, 20);
+    assert_eq!(controller.answer, 42);
+    assert_eq!(controller.number_service.get_num(), 10);
+    assert_eq!(controller.config.number, 10);
+
+    let tuple: Tuple = provider.resolve();
+    assert_eq!(tuple.0, 0);
+    assert_eq!(tuple.1.get_num(), 10);
+
+    let shadowing: Shadowing = provider.resolve();
+    assert_eq!(shadowing.config.number, 10);
+    assert_eq!(shadowing.first.0 + shadowing.second, 2);
+}
@@ -0,0 +1,26 @@
+use teloc::{Dependency, Resolver, ServiceProvider};
+
+struct Number(u8);
+impl Number {
+    fn init(number: u8) -> Self {
+        Number(number)
+    }
+}
+
+#[derive(Dependency)]
+struct Foo(#[init(5)] Number);
+
+#[derive(Dependency)]
+struct Bar(Foo, #[init(10)] Number, Foo);
+
+#[test]
+fn test() {
+    let provider = ServiceProvider::new()
+        .add_transient::<Foo>()
+        .add_transient::<Bar>();
+    let bar: Bar = provider.resolve();
+
+    assert_eq!((bar.0).0 .0, 5);
+    assert_eq!(bar.1 .0, 10);
+    assert_eq!((bar.2).0 .0, 5);
+}
@@ -1,6 +1,7 @@
 use teloc::Dependency;
 
 #[derive(Dependency)]
+#[allow(dead_code)]
 struct Unit;
 
 #[test]
@@ -0,0 +1,75 @@
+use std::sync::Arc;
+use teloc::*;
+
+trait NumberService {
+    fn get_num(&self) -> i32;
+}
+
+struct ConstService;
+impl NumberService for ConstService {
+    fn get_num(&self) -> i32 {
+        10
+    }
+}
+#[inject(provides = dyn NumberService)]
+impl ConstService {
+    fn new() -> Self {
+        ConstService
+    }
+}
+
+#[test]
+fn test_describe() {
+    let container = ServiceProvider::new()
+        .add_singleton::<Arc<ConstService>>()
+        .add_forward::<dyn NumberService, Arc<ConstService>>()
+        .add_transient_c::<Box<dyn NumberService>, Box<ConstService>>();
+
+    assert_eq!(
+        container.describe(),
+        [
+            ServiceDescriptor {
+                type_name: std::any::type_name::<Box<dyn NumberService>>(),
+                lifetime: ServiceLifetime::Transient,
+                converts_from: Some(std::any::type_name::<Box<ConstService>>()),
+                initialised: false,
+            },
+            ServiceDescriptor {
+                type_name: std::any::type_name::<Arc<dyn NumberService>>(),
+                lifetime: ServiceLifetime::Forward,
+                converts_from: Some(std::any::type_name::<Arc<ConstService>>()),
+                initialised: false,
+            },
+            ServiceDescriptor {
+                type_name: std::any::type_name::<Arc<ConstService>>(),
+                lifetime: ServiceLifetime::Singleton,
+                converts_from: None,
+                initialised: false,
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_describe_initialised() {
+    let container = ServiceProvider::new().add_singleton_as::<dyn NumberService, ConstService>();
+    assert!(!container.describe()[0].initialised);
+
+    let service: Arc<dyn NumberService> = container.resolve();
+    assert_eq!(service.get_num(), 10);
+    assert!(container.describe()[0].initialised);
+}
+
+#[test]
+fn test_describe_parents() {
+    let container = Arc::new(ServiceProvider::new().add_instance(10u8));
+    let scope = container.fork_arc().add_instance(20u16);
+    let scope = scope.fork().add_instance(30u32);
+
+    let names = scope
+        .describe()
+        .into_iter()
+        .map(|d| d.type_name)
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["u32", "u16", "u8"]);
+}
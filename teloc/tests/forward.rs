@@ -0,0 +1,80 @@
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Arc;
+use teloc::*;
+
+trait Repo {
+    fn save(&self);
+}
+trait HealthCheck {
+    fn saved(&self) -> u8;
+}
+
+struct Database {
+    saved: AtomicU8,
+}
+impl Repo for Database {
+    fn save(&self) {
+        self.saved.fetch_add(1, Ordering::SeqCst);
+    }
+}
+impl HealthCheck for Database {
+    fn saved(&self) -> u8 {
+        self.saved.load(Ordering::SeqCst)
+    }
+}
+#[inject(provides = dyn Repo, provides = dyn HealthCheck)]
+impl Database {
+    fn new() -> Self {
+        Database {
+            saved: AtomicU8::new(0),
+        }
+    }
+}
+
+#[derive(Dependency)]
+struct Controller {
+    repo: Arc<dyn Repo>,
+    health: Arc<dyn HealthCheck>,
+}
+
+#[test]
+fn test_forward_singleton() {
+    let container = ServiceProvider::new()
+        .add_singleton::<Arc<Database>>()
+        .add_forward::<dyn Repo, Arc<Database>>()
+        .add_forward::<dyn HealthCheck, Arc<Database>>()
+        .add_transient::<Controller>();
+    let controller: Controller = container.resolve();
+    controller.repo.save();
+    controller.repo.save();
+
+    assert_eq!(controller.health.saved(), 2);
+}
+
+#[test]
+fn test_forward_from_parent() {
+    let container = ServiceProvider::new()
+        .add_singleton::<Arc<Database>>()
+        .add_forward::<dyn Repo, Arc<Database>>();
+    let scope = container
+        .fork()
+        .add_forward::<dyn HealthCheck, Arc<Database>>();
+    let repo: Arc<dyn Repo> = scope.resolve();
+    let health: Arc<dyn HealthCheck> = scope.resolve();
+    repo.save();
+
+    assert_eq!(health.saved(), 1);
+}
+
+#[test]
+fn test_forward_rc_instance() {
+    let database = Rc::new(Database::new());
+    let container = ServiceProvider::new()
+        .add_instance(database.clone())
+        .add_forward::<dyn Repo, Rc<Database>>();
+    let repo: Rc<dyn Repo> = container.resolve();
+    repo.save();
+
+    assert_eq!(database.saved(), 1);
+}
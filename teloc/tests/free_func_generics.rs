@@ -0,0 +1,81 @@
+use teloc::{inject, Dependency, Resolver, ServiceProvider};
+
+trait Repo {
+    fn get(&self) -> i32;
+}
+
+struct ConstRepo(i32);
+impl Repo for ConstRepo {
+    fn get(&self) -> i32 {
+        self.0
+    }
+}
+#[inject]
+fn create_repo() -> ConstRepo {
+    ConstRepo(10)
+}
+
+struct Service<T> {
+    repo: T,
+}
+
+#[inject]
+fn make<T: Repo>(repo: T) -> Service<T> {
+    Service { repo }
+}
+
+struct RefService<'a, T>
+where
+    T: Repo,
+{
+    repo: &'a T,
+}
+
+#[inject]
+fn make_ref<'a, T>(repo: &'a T) -> RefService<'a, T>
+where
+    T: Repo,
+{
+    RefService { repo }
+}
+
+struct Doubled(i32);
+
+#[inject]
+fn make_doubled(repo: &impl Repo) -> Doubled {
+    Doubled(repo.get() * 2)
+}
+
+#[test]
+fn test_generic_function() {
+    let provider = ServiceProvider::new()
+        .add_transient::<ConstRepo>()
+        .add_transient::<Service<ConstRepo>>();
+    let service: Service<ConstRepo> = provider.resolve();
+
+    assert_eq!(service.repo.get(), 10);
+}
+
+#[test]
+fn test_generic_function_with_where_clause() {
+    let provider = ServiceProvider::new()
+        .add_instance(ConstRepo(5))
+        .add_transient::<RefService<ConstRepo>>();
+    let service: RefService<ConstRepo> = provider.resolve();
+
+    assert_eq!(service.repo.get(), 5);
+}
+
+#[test]
+fn test_impl_trait_arg() {
+    let provider = ServiceProvider::new()
+        .add_instance(ConstRepo(5))
+        .add_transient::<Doubled>();
+    let doubled: Doubled = provider.resolve();
+
+    assert_eq!(doubled.0, 10);
+    assert_eq!(
+        Doubled::init(teloc::reexport::frunk::hlist![&ConstRepo(1)]).0,
+        2
+    );
+}
@@ -0,0 +1,126 @@
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use teloc::*;
+
+trait Entity: Send + Sync + 'static {
+    const TABLE: &'static str;
+}
+
+struct User;
+impl Entity for User {
+    const TABLE: &'static str = "users";
+}
+
+struct Order;
+impl Entity for Order {
+    const TABLE: &'static str = "orders";
+}
+
+struct Database {
+    url: String,
+}
+
+struct Repositories;
+
+struct Repository<T> {
+    table: String,
+    entity: PhantomData<T>,
+}
+#[inject(family = Repositories)]
+impl<T: Entity> Repository<T> {
+    fn new(db: &Database) -> Self {
+        Repository {
+            table: format!("{}/{}", db.url, T::TABLE),
+            entity: PhantomData,
+        }
+    }
+}
+
+struct Services;
+
+struct Service<T: Entity> {
+    repository: Arc<Repository<T>>,
+    users: Arc<Repository<User>>,
+}
+#[inject(family = Services)]
+impl<T: Entity> Service<T> {
+    fn new(repository: Arc<Repository<T>>, users: Arc<Repository<User>>) -> Self {
+        Service { repository, users }
+    }
+}
+
+#[test]
+fn test_generic_container() {
+    let container = ServiceProvider::new()
+        .add_instance(Database {
+            url: "db".to_string(),
+        })
+        .add_generic::<Repositories>()
+        .add_generic::<Services>();
+    let scope = container.fork();
+
+    let orders: Arc<Service<Order>> = scope.resolve();
+    let users: Arc<Service<User>> = scope.resolve();
+
+    assert_eq!(orders.repository.table, "db/orders");
+    assert_eq!(users.repository.table, "db/users");
+    assert!(Arc::ptr_eq(&orders.users, &users.repository));
+
+    let orders2: Arc<Service<Order>> = container.resolve();
+    assert!(Arc::ptr_eq(&orders, &orders2));
+}
+
+#[test]
+fn test_generic_container_describe() {
+    let container = ServiceProvider::new()
+        .add_instance(Database {
+            url: "db".to_string(),
+        })
+        .add_generic::<Repositories>();
+    assert!(!container.describe()[0].initialised);
+
+    let _: Arc<Repository<User>> = container.resolve();
+    assert_eq!(
+        container.describe()[0],
+        ServiceDescriptor {
+            type_name: std::any::type_name::<Repositories>(),
+            lifetime: ServiceLifetime::Singleton,
+            converts_from: None,
+            initialised: true,
+        }
+    );
+}
+
+struct Pools;
+
+static CREATED_POOLS: AtomicUsize = AtomicUsize::new(0);
+
+struct Pool<T> {
+    entity: PhantomData<T>,
+}
+#[inject(family = Pools)]
+impl<T: Entity> Pool<T> {
+    fn new() -> Self {
+        CREATED_POOLS.fetch_add(1, Ordering::SeqCst);
+        thread::sleep(Duration::from_millis(50));
+        Pool {
+            entity: PhantomData,
+        }
+    }
+}
+
+#[test]
+fn test_generic_container_creates_member_once() {
+    let container = ServiceProvider::new().add_generic::<Pools>();
+
+    let pools: Vec<Arc<Pool<User>>> = thread::scope(|s| {
+        let handles: Vec<_> = (0..4).map(|_| s.spawn(|| container.resolve())).collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+
+    assert_eq!(CREATED_POOLS.load(Ordering::SeqCst), 1);
+    assert!(pools.iter().all(|pool| Arc::ptr_eq(pool, &pools[0])));
+}
@@ -0,0 +1,84 @@
+use teloc::*;
+
+struct Config {
+    name: &'static str,
+}
+
+struct Database {
+    name: &'static str,
+}
+#[inject]
+impl Database {
+    fn new(config: &Config) -> Self {
+        Database { name: config.name }
+    }
+}
+
+struct Service<'a> {
+    db: &'a Database,
+    request: &'a u32,
+}
+#[inject]
+impl<'a> Service<'a> {
+    fn new(db: &'a Database, request: &'a u32) -> Self {
+        Service { db, request }
+    }
+}
+
+global_provider! {
+    static PROVIDER: [
+        TransientContainer<Service<'static>>,
+        SingletonContainer<Database>,
+        InstanceContainer<Config>,
+    ] = ServiceProvider::const_builder()
+        .add_instance(Config { name: "main" })
+        .add_singleton::<Database>()
+        .add_transient::<Service>()
+        .build();
+    fn resolve_global() -> [&'static Database, &'static Config];
+}
+
+global_provider! {
+    static OTHER: [TransientContainer<Database>, InstanceContainer<Config>] =
+        ServiceProvider::const_builder()
+            .add_instance(Config { name: "other" })
+            .add_transient::<Database>()
+            .build();
+    fn resolve_other() -> [&'static Config, Database];
+}
+
+#[test]
+fn test_global_provider() {
+    let db1: &Database = resolve_global();
+    let db2 = resolve_global::<&Database>();
+
+    assert_eq!(db1.name, "main");
+    assert!(std::ptr::eq(db1, db2));
+}
+
+#[test]
+fn test_several_global_providers() {
+    assert_eq!(resolve_global::<&Config>().name, "main");
+    assert_eq!(resolve_other::<&Config>().name, "other");
+    assert_eq!(resolve_other::<Database>().name, "other");
+}
+
+#[test]
+fn test_global_provider_fork() {
+    let scope = PROVIDER.fork().add_instance(10u32);
+    let service: Service = scope.resolve();
+
+    assert_eq!(*service.request, 10);
+    assert!(std::ptr::eq(service.db, resolve_global::<&Database>()));
+}
+
+#[test]
+fn test_const_builder_at_runtime() {
+    let provider = ServiceProvider::const_builder()
+        .add_instance(Config { name: "local" })
+        .add_transient::<Database>()
+        .build();
+    let db: Database = provider.resolve();
+
+    assert_eq!(db.name, "local");
+}
@@ -0,0 +1,116 @@
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+use teloc::*;
+
+struct Order {
+    id: u32,
+}
+
+struct Repo {
+    orders: Mutex<Vec<u32>>,
+}
+#[inject]
+impl Repo {
+    fn new() -> Self {
+        Repo {
+            orders: Mutex::new(vec![]),
+        }
+    }
+}
+
+struct Mailer {
+    from: &'static str,
+}
+
+struct Request<'a> {
+    user: &'a str,
+}
+#[inject]
+impl<'a> Request<'a> {
+    fn new(user: &'a String) -> Self {
+        Request { user }
+    }
+}
+
+#[handler]
+async fn process(order: Order, repo: &Repo, mailer: Arc<Mailer>) -> String {
+    repo.orders.lock().unwrap().push(order.id);
+    format!("{}: order {}", mailer.from, order.id)
+}
+
+#[handler(Greet)]
+async fn greet_user(greeting: &'static str, request: Request<'_>) -> String {
+    format!("{}, {}", greeting, request.user)
+}
+
+#[handler(?Send)]
+async fn count(_: (), repo: Rc<usize>) -> usize {
+    *repo
+}
+
+fn assert_send<T: Send>(_: &T) {}
+
+#[tokio::test]
+async fn test_handler() {
+    let sp = ServiceProvider::new()
+        .add_singleton::<Repo>()
+        .add_instance(Arc::new(Mailer { from: "shop" }));
+
+    let res = sp.invoke(&Process, Order { id: 1 }).await;
+    assert_eq!(res, "shop: order 1");
+
+    let scope = sp.fork();
+    let future = scope.invoke(&Process, Order { id: 2 });
+    assert_send(&future);
+    assert_eq!(future.await, "shop: order 2");
+
+    let repo: &Repo = sp.resolve();
+    assert_eq!(*repo.orders.lock().unwrap(), [1, 2]);
+}
+
+#[tokio::test]
+async fn test_handler_with_lifetimes() {
+    let sp = ServiceProvider::new().add_transient::<Request>();
+    let scope = sp.fork().add_instance("Alice".to_string());
+
+    assert_eq!(scope.invoke(&Greet, "Hello").await, "Hello, Alice");
+}
+
+#[tokio::test]
+async fn test_handler_not_send() {
+    let sp = ServiceProvider::new().add_instance(Rc::new(3usize));
+
+    assert_eq!(sp.invoke(&Count, ()).await, 3);
+}
+
+#[handler]
+async fn refund(order: Order, repo: &Repo) -> String {
+    repo.orders.lock().unwrap().retain(|id| *id != order.id);
+    format!("refund {}", order.id)
+}
+
+#[tokio::test]
+async fn test_handler_router() {
+    type SP = ServiceProvider<
+        EmptyServiceProvider,
+        teloc::reexport::HList![InstanceContainer<Arc<Mailer>>, SingletonContainer<Repo>],
+    >;
+
+    let mut router: HandlerRouter<SP, Order, String> = HandlerRouter::new();
+    router
+        .route("process", |sp, order| sp.invoke(&Process, order))
+        .route("refund", |sp, order| sp.invoke(&Refund, order));
+
+    let sp = ServiceProvider::new()
+        .add_singleton::<Repo>()
+        .add_instance(Arc::new(Mailer { from: "shop" }));
+
+    let res = router.dispatch(&sp, "process", Order { id: 1 }).unwrap();
+    assert_eq!(res.await, "shop: order 1");
+    let res = router.dispatch(&sp, "refund", Order { id: 1 }).unwrap();
+    assert_eq!(res.await, "refund 1");
+    assert!(router.dispatch(&sp, "cancel", Order { id: 1 }).is_none());
+
+    let repo: &Repo = sp.resolve();
+    assert!(repo.orders.lock().unwrap().is_empty());
+}
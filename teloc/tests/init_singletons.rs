@@ -0,0 +1,199 @@
+use std::cell::Cell;
+use std::rc::Rc;
+use teloc::*;
+
+struct Counter(Rc<Cell<u8>>);
+#[inject]
+impl Counter {
+    fn new(calls: &Rc<Cell<u8>>) -> Self {
+        calls.set(calls.get() + 1);
+        Counter(calls.clone())
+    }
+}
+
+#[cfg(feature = "std")]
+struct Config;
+#[cfg(feature = "std")]
+#[inject]
+impl Config {
+    fn new() -> Self {
+        panic!("missing DATABASE_URL")
+    }
+}
+
+#[cfg(feature = "std")]
+struct Database<'a> {
+    _config: &'a Config,
+}
+#[cfg(feature = "std")]
+#[inject]
+impl<'a> Database<'a> {
+    fn new(config: &'a Config) -> Self {
+        Database { _config: config }
+    }
+}
+
+struct Connection {
+    url: &'static str,
+}
+impl<'a> TryDependency<teloc::reexport::HList![&'a &'static str]> for Connection {
+    type Error = String;
+
+    fn try_init(
+        teloc::reexport::hlist_pat![url]: teloc::reexport::HList![&'a &'static str],
+    ) -> Result<Self, String> {
+        if url.is_empty() {
+            Err("empty url".to_string())
+        } else {
+            Ok(Connection { url })
+        }
+    }
+}
+
+#[test]
+fn test_init_singletons() {
+    let calls = Rc::new(Cell::new(0));
+    let container = ServiceProvider::new()
+        .add_instance(calls.clone())
+        .add_singleton::<Counter>()
+        .add_transient::<u8>();
+
+    container.init_singletons().unwrap();
+    assert_eq!(calls.get(), 1);
+
+    let counter: &Counter = container.resolve();
+    assert_eq!(counter.0.get(), 1);
+    container.init_singletons().unwrap();
+    assert_eq!(calls.get(), 1);
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn test_init_singletons_aggregates_failures() {
+    let calls = Rc::new(Cell::new(0));
+    let container = ServiceProvider::new()
+        .add_instance(calls.clone())
+        .add_singleton::<Config>()
+        .add_singleton::<Database>()
+        .add_singleton::<Counter>();
+
+    let error = container.init_singletons().unwrap_err();
+    assert_eq!(
+        error.failures,
+        [
+            SingletonInitFailure {
+                type_name: std::any::type_name::<Database>(),
+                message: "missing DATABASE_URL".to_string(),
+            },
+            SingletonInitFailure {
+                type_name: std::any::type_name::<Config>(),
+                message: "missing DATABASE_URL".to_string(),
+            },
+        ]
+    );
+    assert_eq!(calls.get(), 1);
+    assert!(error
+        .to_string()
+        .starts_with("failed to initialise 2 singleton(s):"));
+}
+
+struct First;
+#[inject]
+impl First {
+    fn new() -> Self {
+        First
+    }
+}
+
+struct Second;
+#[inject]
+impl Second {
+    fn new() -> Self {
+        Second
+    }
+}
+
+struct Third;
+#[inject]
+impl Third {
+    fn new() -> Self {
+        Third
+    }
+}
+
+#[tokio::test]
+async fn test_init_singletons_async_initialises_all() {
+    let calls = Rc::new(Cell::new(0));
+    let container = ServiceProvider::new()
+        .add_instance(calls.clone())
+        .add_singleton::<Counter>()
+        .add_singleton::<First>()
+        .add_singleton::<Second>()
+        .add_singleton::<Third>();
+
+    container.init_singletons_async().await.unwrap();
+    let descriptors = container.describe();
+    assert_eq!(descriptors.len(), 5);
+    assert!(descriptors.iter().all(|d| d.initialised));
+    assert_eq!(calls.get(), 1);
+}
+
+#[tokio::test]
+async fn test_init_singletons_async() {
+    let calls = Rc::new(Cell::new(0));
+    let container = ServiceProvider::new()
+        .add_instance(calls.clone())
+        .add_singleton::<Counter>()
+        .add_transient::<u8>();
+
+    container.init_singletons_async().await.unwrap();
+    assert_eq!(calls.get(), 1);
+}
+
+#[tokio::test]
+#[cfg(feature = "std")]
+async fn test_init_singletons_async_aggregates_failures() {
+    let calls = Rc::new(Cell::new(0));
+    let container = ServiceProvider::new()
+        .add_instance(calls.clone())
+        .add_singleton::<Counter>()
+        .add_singleton::<Config>();
+
+    let error = container.init_singletons_async().await.unwrap_err();
+    assert_eq!(error.failures.len(), 1);
+    assert_eq!(calls.get(), 1);
+}
+
+#[test]
+fn test_init_singletons_reports_errors() {
+    let container = ServiceProvider::new()
+        .add_instance("")
+        .add_singleton::<Result<Connection, String>>();
+
+    let error = container.init_singletons().unwrap_err();
+    assert_eq!(
+        error.failures,
+        [SingletonInitFailure {
+            type_name: std::any::type_name::<Result<Connection, String>>(),
+            message: "empty url".to_string(),
+        }]
+    );
+
+    let container = ServiceProvider::new()
+        .add_instance("db")
+        .add_singleton::<Result<Connection, String>>();
+
+    container.init_singletons().unwrap();
+    let connection: &Result<Connection, String> = container.resolve();
+    assert_eq!(connection.as_ref().unwrap().url, "db");
+}
+
+#[tokio::test]
+async fn test_init_singletons_async_reports_errors() {
+    let container = ServiceProvider::new()
+        .add_instance("")
+        .add_singleton::<Result<Connection, String>>();
+
+    let error = container.init_singletons_async().await.unwrap_err();
+    assert_eq!(error.failures[0].message, "empty url");
+}
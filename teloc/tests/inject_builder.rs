@@ -0,0 +1,110 @@
+use teloc::{inject, Resolver, ServiceProvider};
+
+struct Config {
+    url: &'static str,
+}
+
+struct Pool {
+    url: &'static str,
+    size: u8,
+    retries: u8,
+}
+
+struct PoolBuilder {
+    url: &'static str,
+    size: u8,
+    retries: u8,
+}
+
+#[inject(builder)]
+impl PoolBuilder {
+    pub fn new(config: &Config) -> Self {
+        PoolBuilder {
+            url: config.url,
+            size: 1,
+            retries: 0,
+        }
+    }
+
+    #[inject::step]
+    pub fn with_size(mut self, size: &u8) -> Self {
+        self.size = *size;
+        self
+    }
+
+    // Not marked as a step, so it is not called
+    pub fn with_retries(self) -> PoolBuilder {
+        PoolBuilder { retries: 3, ..self }
+    }
+
+    pub fn size(&self) -> u8 {
+        self.size
+    }
+
+    pub fn build(self) -> Pool {
+        Pool {
+            url: self.url,
+            size: self.size,
+            retries: self.retries,
+        }
+    }
+}
+
+struct Client<'a> {
+    config: &'a Config,
+}
+
+struct ClientBuilder<'a> {
+    config: Option<&'a Config>,
+}
+
+#[inject(builder)]
+impl<'a> ClientBuilder<'a> {
+    #[inject::init]
+    pub fn new() -> Self {
+        ClientBuilder { config: None }
+    }
+
+    pub fn empty() -> Self {
+        ClientBuilder { config: None }
+    }
+
+    #[inject::step]
+    pub fn config(self, config: &'a Config) -> Self {
+        ClientBuilder {
+            config: Some(config),
+        }
+    }
+
+    #[inject::build]
+    pub fn build(self) -> Client<'a> {
+        Client {
+            config: self.config.unwrap(),
+        }
+    }
+
+    pub fn try_build(self) -> Option<Client<'a>> {
+        self.config.map(|config| Client { config })
+    }
+}
+
+#[test]
+fn test_builder() {
+    let provider = ServiceProvider::new()
+        .add_instance(Config { url: "localhost" })
+        .add_instance(10u8)
+        .add_singleton::<Pool>()
+        .add_transient::<Client>();
+
+    let pool: &Pool = provider.resolve();
+    assert_eq!(pool.url, "localhost");
+    assert_eq!(pool.size, 10);
+    assert_eq!(pool.retries, 0);
+    let builder = PoolBuilder::new(&Config { url: "" });
+    assert_eq!(builder.size(), 1);
+    assert_eq!(builder.with_retries().build().retries, 3);
+
+    let client: Client = provider.resolve();
+    assert_eq!(client.config.url, "localhost");
+    assert!(ClientBuilder::empty().try_build().is_none());
+}
@@ -0,0 +1,38 @@
+#![cfg(feature = "std")]
+
+use std::sync::{Arc, Mutex, RwLock};
+use teloc::*;
+
+#[derive(Default)]
+struct Counter {
+    count: u32,
+}
+#[inject]
+impl Counter {
+    fn new() -> Self {
+        Counter::default()
+    }
+}
+
+#[test]
+fn test_mutex_singleton() {
+    let container = ServiceProvider::new().add_singleton::<Arc<Mutex<Counter>>>();
+
+    let c1: Arc<Mutex<Counter>> = container.resolve();
+    let c2: Arc<Mutex<Counter>> = container.resolve();
+    c1.lock().unwrap().count += 1;
+    c2.lock().unwrap().count += 1;
+
+    assert_eq!(c1.lock().unwrap().count, 2);
+}
+
+#[test]
+fn test_rwlock_by_reference() {
+    let container = ServiceProvider::new().add_singleton::<RwLock<Counter>>();
+
+    let counter: &RwLock<Counter> = container.resolve();
+    counter.write().unwrap().count += 1;
+
+    let counter: &RwLock<Counter> = container.resolve();
+    assert_eq!(counter.read().unwrap().count, 1);
+}
@@ -0,0 +1,57 @@
+use std::rc::Rc;
+use teloc::reexport::frunk::{HCons, HNil};
+use teloc::*;
+
+struct Config {
+    number: i32,
+}
+
+struct ConstService {
+    number: i32,
+}
+#[inject]
+impl ConstService {
+    fn new(config: &Config) -> Self {
+        ConstService {
+            number: config.number,
+        }
+    }
+}
+
+#[derive(Dependency)]
+struct Controller {
+    service: ConstService,
+    shared: Rc<Config>,
+}
+
+type ConfigModule = ServiceProvider<
+    EmptyServiceProvider,
+    HCons<InstanceContainer<Rc<Config>>, HCons<InstanceContainer<Config>, HNil>>,
+>;
+
+fn config_module() -> ConfigModule {
+    ServiceProvider::new()
+        .add_instance(Config { number: 10 })
+        .add_instance(Rc::new(Config { number: 20 }))
+}
+
+#[test]
+fn test_merge() {
+    let sp = ServiceProvider::new()
+        .add_transient::<ConstService>()
+        .add_transient::<Controller>()
+        .merge(config_module());
+
+    let controller: Controller = sp.resolve();
+    assert_eq!(controller.service.number, 10);
+    assert_eq!(controller.shared.number, 20);
+}
+
+#[test]
+fn test_merge_into_fork() {
+    let sp = ServiceProvider::new().add_transient::<ConstService>();
+    let scope = sp.fork().merge(config_module());
+
+    let service: ConstService = scope.resolve();
+    assert_eq!(service.number, 10);
+}
@@ -0,0 +1,103 @@
+use teloc::{inject, Dependency, Resolver, ServiceProvider};
+
+struct Clock {
+    now: u64,
+}
+
+struct MockTime(u64);
+
+#[inject]
+impl Clock {
+    #[inject::init]
+    pub fn new() -> Self {
+        Clock { now: 1_600_000_000 }
+    }
+
+    #[inject::init]
+    pub fn with_mock_clock(time: &MockTime) -> Self {
+        Clock { now: time.0 }
+    }
+
+    #[inject::init(Zero)]
+    pub fn zero() -> Self {
+        Clock { now: 0 }
+    }
+}
+
+// Markers of `new` constructors of both types are generated in one module
+struct Timer {
+    ticks: u32,
+}
+
+#[inject]
+impl Timer {
+    #[inject::init]
+    pub fn new() -> Self {
+        Timer { ticks: 0 }
+    }
+
+    #[inject::init]
+    pub fn started() -> Self {
+        Timer { ticks: 1 }
+    }
+}
+
+#[derive(Dependency)]
+struct Controller {
+    clock: Clock,
+}
+
+#[test]
+fn test_default_constructor() {
+    let provider = ServiceProvider::new()
+        .add_transient::<Clock>()
+        .add_transient::<Controller>();
+    let controller: Controller = provider.resolve();
+
+    assert_eq!(controller.clock.now, 1_600_000_000);
+}
+
+#[test]
+fn test_chosen_constructor() {
+    let provider = ServiceProvider::new()
+        .add_instance(MockTime(5))
+        .add_transient_with::<Clock, ClockWithMockClock>()
+        .add_transient::<Controller>();
+    let controller: Controller = provider.resolve();
+
+    assert_eq!(controller.clock.now, 5);
+}
+
+#[test]
+fn test_named_constructor() {
+    let provider = ServiceProvider::new().add_transient_with::<Clock, Zero>();
+    let clock: Clock = provider.resolve();
+
+    assert_eq!(clock.now, 0);
+}
+
+#[test]
+fn test_markers_of_different_types() {
+    let provider = ServiceProvider::new()
+        .add_transient_with::<Clock, ClockNew>()
+        .add_transient_with::<Timer, TimerNew>();
+    let clock: Clock = provider.resolve();
+    let timer: Timer = provider.resolve();
+
+    assert_eq!(clock.now, 1_600_000_000);
+    assert_eq!(timer.ticks, 0);
+}
+
+#[test]
+fn test_singleton_with_chosen_constructor() {
+    let provider = ServiceProvider::new()
+        .add_instance(MockTime(5))
+        .add_singleton_with::<Clock, ClockWithMockClock>()
+        .add_singleton_with::<Timer, TimerStarted>();
+    let clock: &Clock = provider.resolve();
+    let timer: &Timer = provider.resolve();
+
+    assert_eq!(clock.now, 5);
+    assert_eq!(timer.ticks, 1);
+    assert!(provider.describe()[1].initialised);
+}
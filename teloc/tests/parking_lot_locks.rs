@@ -0,0 +1,31 @@
+#![cfg(feature = "parking-lot-support")]
+
+use parking_lot::{Mutex, RwLock};
+use std::sync::Arc;
+use teloc::*;
+
+#[derive(Default)]
+struct Counter {
+    count: u32,
+}
+#[inject]
+impl Counter {
+    fn new() -> Self {
+        Counter::default()
+    }
+}
+
+#[test]
+fn test_parking_lot_locks() {
+    let container = ServiceProvider::new()
+        .add_singleton::<Arc<Mutex<Counter>>>()
+        .add_transient::<RwLock<Counter>>();
+
+    let mutex: Arc<Mutex<Counter>> = container.resolve();
+    mutex.lock().count += 1;
+    let mutex: Arc<Mutex<Counter>> = container.resolve();
+    assert_eq!(mutex.lock().count, 1);
+
+    let rwlock: RwLock<Counter> = container.resolve();
+    assert_eq!(rwlock.read().count, 0);
+}
@@ -0,0 +1,73 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use teloc::{inject, Resolver, ServiceProvider};
+
+#[derive(Default)]
+struct EventBus {
+    subscribers: RefCell<Vec<&'static str>>,
+}
+
+struct Config {
+    name: &'static str,
+}
+
+struct Service {
+    name: &'static str,
+    started: bool,
+}
+
+#[inject]
+impl Service {
+    #[inject::init]
+    fn new(config: &Config) -> Self {
+        Service {
+            name: config.name,
+            started: false,
+        }
+    }
+
+    #[inject::post_construct]
+    fn start(&mut self, bus: Rc<EventBus>) {
+        bus.subscribers.borrow_mut().push(self.name);
+        self.started = true;
+    }
+}
+
+struct Cache {
+    warmed: RefCell<bool>,
+}
+
+#[inject]
+impl Cache {
+    fn new() -> Self {
+        Cache {
+            warmed: RefCell::new(false),
+        }
+    }
+
+    #[inject::post_construct]
+    fn warm(&self) {
+        *self.warmed.borrow_mut() = true;
+    }
+}
+
+#[test]
+fn test_post_construct() {
+    let bus = Rc::new(EventBus::default());
+    let provider = ServiceProvider::new()
+        .add_instance(Config { name: "service" })
+        .add_instance(bus.clone())
+        .add_transient::<Service>();
+
+    let service: Service = provider.resolve();
+    assert!(service.started);
+    assert_eq!(*bus.subscribers.borrow(), vec!["service"]);
+}
+
+#[test]
+fn test_post_construct_without_init_annotation() {
+    let provider = ServiceProvider::new().add_singleton::<Cache>();
+
+    let cache: &Cache = provider.resolve();
+    assert!(*cache.warmed.borrow());
+}
@@ -0,0 +1,81 @@
+use std::rc::Rc;
+use std::sync::Arc;
+use teloc::*;
+
+struct NumberServiceOptions(i32);
+
+trait NumberService {
+    fn get_num(&self) -> i32;
+}
+trait NameService {
+    fn name(&self) -> &str;
+}
+
+struct ConstService {
+    number: i32,
+}
+impl NumberService for ConstService {
+    fn get_num(&self) -> i32 {
+        self.number
+    }
+}
+impl NameService for ConstService {
+    fn name(&self) -> &str {
+        "const"
+    }
+}
+#[inject(provides = dyn NumberService, provides = dyn NameService)]
+impl ConstService {
+    fn new(options: &NumberServiceOptions) -> Self {
+        ConstService { number: options.0 }
+    }
+}
+
+#[derive(Dependency)]
+struct Controller {
+    number_service: Box<dyn NumberService>,
+}
+
+#[test]
+fn test_transient_as() {
+    let container = ServiceProvider::new()
+        .add_instance(NumberServiceOptions(10))
+        .add_transient_as::<dyn NumberService, ConstService>()
+        .add_transient_as::<dyn NameService, ConstService>()
+        .add_transient::<Controller>();
+    let controller: Controller = container.resolve();
+    let name: Box<dyn NameService> = container.resolve();
+
+    assert_eq!(controller.number_service.get_num(), 10);
+    assert_eq!(name.name(), "const");
+}
+
+#[test]
+fn test_singleton_as() {
+    let container = ServiceProvider::new()
+        .add_instance(NumberServiceOptions(10))
+        .add_singleton_as::<dyn NumberService, ConstService>();
+    let first: Arc<dyn NumberService> = container.resolve();
+    let second: Arc<dyn NumberService> = container.resolve();
+
+    assert_eq!(first.get_num(), 10);
+    assert!(Arc::ptr_eq(&first, &second));
+}
+
+#[test]
+fn test_generated_from() {
+    let container = ServiceProvider::new()
+        .add_instance(NumberServiceOptions(10))
+        .add_transient_c::<Box<dyn NumberService>, Box<ConstService>>();
+    let service: Box<dyn NumberService> = container.resolve();
+
+    assert_eq!(service.get_num(), 10);
+}
+
+#[test]
+fn test_provide_rc() {
+    let service = Rc::new(ConstService { number: 5 });
+    let service: Rc<dyn NumberService> = service.provide_rc();
+
+    assert_eq!(service.get_num(), 5);
+}
@@ -0,0 +1,78 @@
+use std::rc::Rc;
+use teloc::reexport::{hlist_pat, HList};
+use teloc::*;
+
+struct Config {
+    name: &'static str,
+}
+
+struct Repo {
+    name: &'static str,
+}
+#[inject]
+impl Repo {
+    fn new(config: &Config) -> Self {
+        Repo { name: config.name }
+    }
+}
+
+struct Mailer {
+    repo: Rc<Repo>,
+}
+#[inject]
+impl Mailer {
+    fn new(repo: Rc<Repo>) -> Self {
+        Mailer { repo }
+    }
+}
+
+#[test]
+fn test_resolve_tuple() {
+    let sp = ServiceProvider::new()
+        .add_instance(Config { name: "db" })
+        .add_singleton::<Rc<Repo>>()
+        .add_transient::<Mailer>();
+
+    let (repo, config, mailer): (Rc<Repo>, &Config, Mailer) = sp.resolve();
+    assert_eq!(repo.name, config.name);
+    assert!(Rc::ptr_eq(&repo, &mailer.repo));
+
+    let (config,): (&Config,) = sp.resolve();
+    assert_eq!(config.name, "db");
+}
+
+#[test]
+fn test_resolve_hlist() {
+    let sp = ServiceProvider::new()
+        .add_instance(Config { name: "db" })
+        .add_transient::<Repo>();
+    let scope = sp.fork().add_instance(5u8);
+
+    let hlist_pat![number, repo, config]: HList![&u8, Repo, &Config] = scope.resolve();
+    assert_eq!(*number, 5);
+    assert_eq!(repo.name, config.name);
+}
+
+#[test]
+fn test_resolve_nested() {
+    let sp = ServiceProvider::new()
+        .add_instance(Config { name: "db" })
+        .add_transient::<Repo>();
+
+    let (config, (repo, hlist_pat![other])): (&Config, (Repo, HList![Repo])) = sp.resolve();
+    assert_eq!(config.name, repo.name);
+    assert_eq!(repo.name, other.name);
+}
+
+#[test]
+fn test_call_with_tuple_arg() {
+    let sp = ServiceProvider::new()
+        .add_instance(Config { name: "db" })
+        .add_transient::<Repo>();
+
+    let name = sp.call(|(config, repo): (&Config, Repo)| {
+        assert_eq!(config.name, repo.name);
+        repo.name
+    });
+    assert_eq!(name, "db");
+}
@@ -0,0 +1,91 @@
+use std::sync::Arc;
+use teloc::reexport::HList;
+use teloc::*;
+
+struct Config {
+    name: &'static str,
+}
+
+type Root = ServiceProvider<
+    EmptyServiceProvider,
+    HList![
+        TransientContainer<Located>,
+        InstanceContainer<Config>,
+        LocatorContainer
+    ],
+>;
+
+fn root() -> Root {
+    ServiceProvider::new()
+        .add_locator()
+        .add_instance(Config { name: "root" })
+        .add_transient::<Located>()
+}
+
+struct Located {
+    name: Option<&'static str>,
+}
+#[inject]
+impl Located {
+    fn new(locator: &dyn Locator) -> Self {
+        let name = locator.downcast_ref::<Root>().map(|sp| {
+            let config: &Config = sp.resolve();
+            config.name
+        });
+        Located { name }
+    }
+}
+
+#[test]
+fn test_resolve_provider() {
+    let sp = root();
+
+    let resolved: &Root = sp.resolve();
+    assert!(std::ptr::eq(resolved, &sp));
+
+    let name = sp.call(|sp: &Root, config: &Config| {
+        let located: Located = sp.resolve();
+        assert_eq!(located.name, Some(config.name));
+        config.name
+    });
+    assert_eq!(name, "root");
+}
+
+#[test]
+fn test_resolve_locator() {
+    let sp = root();
+
+    let locator: &dyn Locator = sp.resolve();
+    assert!(std::ptr::eq(locator.downcast_ref::<Root>().unwrap(), &sp));
+    assert!(locator
+        .downcast_ref::<ServiceProvider<EmptyServiceProvider, HList![]>>()
+        .is_none());
+}
+
+#[test]
+fn test_resolve_locator_in_scope() {
+    let sp = Arc::new(root());
+    let scope = sp.fork_arc();
+
+    // Locator points at the scope that resolves the service, not at the root provider
+    let located: Located = scope.resolve();
+    assert_eq!(located.name, None);
+
+    let scope_locator: &dyn Locator = scope.resolve();
+    let resolved = scope_locator
+        .downcast_ref::<ServiceProvider<Arc<Root>, HList![]>>()
+        .unwrap();
+    assert!(std::ptr::eq(resolved, &scope));
+
+    let scope_itself: &ServiceProvider<Arc<Root>, HList![]> = scope.resolve();
+    assert!(std::ptr::eq(scope_itself, &scope));
+}
+
+#[test]
+fn test_describe_locator() {
+    let descriptors = root().describe();
+
+    assert!(descriptors
+        .iter()
+        .any(|d| d.type_name.ends_with("Locator") && d.lifetime == ServiceLifetime::Instance));
+}
@@ -0,0 +1,83 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use teloc::*;
+
+struct Message(u32);
+
+struct Counter {
+    total: AtomicU32,
+}
+#[inject]
+impl Counter {
+    fn new() -> Self {
+        Counter {
+            total: Default::default(),
+        }
+    }
+}
+
+struct Consumer<'a> {
+    counter: &'a Counter,
+    message: &'a Message,
+}
+#[inject]
+impl<'a> Consumer<'a> {
+    fn new(counter: &'a Counter, message: &'a Message) -> Self {
+        Self { counter, message }
+    }
+}
+
+async fn consume(consumer: Consumer<'_>, retries: &u8) -> u32 {
+    consumer
+        .counter
+        .total
+        .fetch_add(consumer.message.0 * u32::from(*retries), Ordering::SeqCst)
+        + consumer.message.0 * u32::from(*retries)
+}
+
+#[tokio::test]
+async fn test_scoped_handler() {
+    let sp = Arc::new(
+        ServiceProvider::new()
+            .add_singleton::<Counter>()
+            .add_transient::<Consumer>(),
+    );
+    let handler = ScopedHandler::new(sp, consume);
+
+    let scope = handler.scope(Message(1)).add_instance(1u8);
+    assert_eq!(handler.call(&scope).await, 1);
+
+    let scope = handler.scope(Message(2)).add_instance(2u8);
+    assert_eq!(handler.call(&scope).await, 5);
+}
+
+#[test]
+fn test_scoped_handler_with_closure() {
+    let sp = Arc::new(ServiceProvider::new().add_singleton::<Counter>());
+    let handler = ScopedHandler::new(sp, |message: &Message, counter: &Counter| {
+        counter.total.load(Ordering::SeqCst) + message.0
+    });
+    let scope = handler.scope(Message(3));
+    assert_eq!(handler.call(&scope), 3);
+}
+
+#[tokio::test]
+async fn test_scoped_handler_concurrent_calls() {
+    let sp = Arc::new(
+        ServiceProvider::new()
+            .add_singleton::<Counter>()
+            .add_transient::<Consumer>(),
+    );
+    let handler = ScopedHandler::new(sp, consume);
+
+    let process = |id: u32| {
+        let handler = &handler;
+        async move {
+            let scope = handler.scope(Message(id)).add_instance(1u8);
+            handler.call(&scope).await
+        }
+    };
+    let (first, second) = tokio::join!(process(1), process(2));
+
+    assert_eq!(first.max(second), 3);
+}
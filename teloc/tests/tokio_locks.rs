@@ -0,0 +1,32 @@
+#![cfg(feature = "tokio-support")]
+
+use std::sync::Arc;
+use teloc::*;
+use tokio::sync::{Mutex, RwLock};
+
+#[derive(Default)]
+struct Counter {
+    count: u32,
+}
+#[inject]
+impl Counter {
+    fn new() -> Self {
+        Counter::default()
+    }
+}
+
+#[tokio::test]
+async fn test_tokio_locks() {
+    let container = ServiceProvider::new()
+        .add_singleton::<Arc<Mutex<Counter>>>()
+        .add_singleton::<RwLock<Counter>>();
+
+    let mutex: Arc<Mutex<Counter>> = container.resolve();
+    mutex.lock().await.count += 1;
+    let mutex: Arc<Mutex<Counter>> = container.resolve();
+    assert_eq!(mutex.lock().await.count, 1);
+
+    let rwlock: &RwLock<Counter> = container.resolve();
+    rwlock.write().await.count += 2;
+    assert_eq!(rwlock.read().await.count, 2);
+}
@@ -0,0 +1,119 @@
+#![cfg(feature = "tonic-support")]
+
+use bytes::{Buf, BufMut};
+use http::uri::PathAndQuery;
+use std::convert::Infallible;
+use std::sync::Arc;
+use teloc::*;
+use tonic::codec::{Codec, DecodeBuf, Decoder, EncodeBuf, Encoder};
+use tonic::metadata::MetadataMap;
+use tonic::service::interceptor::InterceptedService;
+use tonic::service::Interceptor;
+use tonic::{Request, Response, Status};
+use tower::service_fn;
+
+#[derive(Clone, Copy)]
+struct StringCodec;
+impl Codec for StringCodec {
+    type Encode = String;
+    type Decode = String;
+    type Encoder = StringCodec;
+    type Decoder = StringCodec;
+
+    fn encoder(&mut self) -> Self::Encoder {
+        *self
+    }
+    fn decoder(&mut self) -> Self::Decoder {
+        *self
+    }
+}
+impl Encoder for StringCodec {
+    type Item = String;
+    type Error = Status;
+
+    fn encode(&mut self, item: String, dst: &mut EncodeBuf<'_>) -> Result<(), Status> {
+        dst.put_slice(item.as_bytes());
+        Ok(())
+    }
+}
+impl Decoder for StringCodec {
+    type Item = String;
+    type Error = Status;
+
+    fn decode(&mut self, src: &mut DecodeBuf<'_>) -> Result<Option<String>, Status> {
+        let bytes = src.copy_to_bytes(src.remaining());
+        String::from_utf8(bytes.to_vec())
+            .map(Some)
+            .map_err(|e| Status::internal(e.to_string()))
+    }
+}
+
+struct User<'a> {
+    name: &'a str,
+}
+#[inject]
+impl<'a> User<'a> {
+    fn new(metadata: &'a MetadataMap) -> Self {
+        Self {
+            name: metadata
+                .get("user")
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or("anonymous"),
+        }
+    }
+}
+
+type AppProvider =
+    ServiceProvider<EmptyServiceProvider, reexport::HList![TransientContainer<User<'static>>]>;
+
+async fn greet(request: Request<String>) -> Result<Response<String>, Status> {
+    let scope = TonicScope::<AppProvider>::from_request(&request)?;
+    let user: User = scope.resolve();
+    Ok(Response::new(format!(
+        "{}, {}",
+        request.get_ref(),
+        user.name
+    )))
+}
+
+async fn call(
+    user: Option<&'static str>,
+    interceptor: impl Interceptor + Send + 'static,
+) -> Result<String, Status> {
+    let server = service_fn(|req: http::Request<tonic::body::Body>| async move {
+        let mut grpc = tonic::server::Grpc::new(StringCodec);
+        Ok::<_, Infallible>(grpc.unary(service_fn(greet), req).await)
+    });
+    let mut client = tonic::client::Grpc::new(InterceptedService::new(server, interceptor));
+
+    let mut request = Request::new("Hello".to_owned());
+    if let Some(user) = user {
+        request.metadata_mut().insert("user", user.parse().unwrap());
+    }
+    client.ready().await.unwrap();
+    client
+        .unary(
+            request,
+            PathAndQuery::from_static("/test.Greeter/Greet"),
+            StringCodec,
+        )
+        .await
+        .map(Response::into_inner)
+}
+
+#[tokio::test]
+async fn test_metadata_injected() {
+    let sp = Arc::new(ServiceProvider::new().add_transient::<User>());
+    let interceptor = TonicScopeInterceptor::new(sp);
+    assert_eq!(
+        call(Some("teloc"), interceptor.clone()).await.unwrap(),
+        "Hello, teloc"
+    );
+    assert_eq!(call(None, interceptor).await.unwrap(), "Hello, anonymous");
+}
+
+#[tokio::test]
+async fn test_without_interceptor() {
+    let status = call(None, Ok).await.unwrap_err();
+    assert_eq!(status.code(), tonic::Code::Internal);
+}
@@ -11,10 +11,12 @@ pub fn compile_error<T: ToTokens>(data: T) -> proc_macro2::TokenStream {
     }
 }
 
+const FIELD_ATTRS: [&str; 4] = ["init", "default", "init_with", "resolve"];
+
 pub fn get_1_teloc_attr(attrs: &[Attribute]) -> Result<Option<&Attribute>, TokenStream> {
     let mut teloc_attrs = vec![];
     attrs.iter().for_each(|attr| {
-        if attr.path.is_ident("init") {
+        if FIELD_ATTRS.iter().any(|name| attr.path.is_ident(name)) {
             teloc_attrs.push(attr);
         }
     });
@@ -22,7 +24,7 @@ pub fn get_1_teloc_attr(attrs: &[Attribute]) -> Result<Option<&Attribute>, Token
         [] => Ok(None),
         [x] => Ok(Some(x)),
         _ => Err(compile_error(format!(
-            "Expected 0 or 1 `clone` or `init` attribute, found {}",
+            "Expected 0 or 1 `init`, `default`, `init_with` or `resolve` attribute, found {}",
             teloc_attrs.len()
         ))),
     }
@@ -65,3 +67,15 @@ pub fn name_generator() -> impl Iterator<Item = String> {
             .map(|arr| arr.iter().join(""))
     })
 }
+
+pub fn to_camel_case(s: &str) -> String {
+    s.split('_')
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
@@ -1,35 +1,52 @@
-use crate::common::{compile_error, get_1_teloc_attr};
+use crate::common::{compile_error, get_1_teloc_attr, name_generator};
 use crate::generics::{get_impl_block_generics, get_struct_block_generics, get_where_clause};
-use proc_macro2::{Ident, TokenStream};
+use proc_macro2::{Ident, Span, TokenStream};
 use quote::quote;
 use syn::parse::{Parse, ParseBuffer};
 use syn::punctuated::Punctuated;
-use syn::Token;
-use syn::{DataStruct, Expr, Field, Fields, Generics, Type};
+use syn::{parenthesized, Token};
+use syn::{DataEnum, DataStruct, Expr, Field, Fields, Generics, Path, Type, Variant};
 
 pub fn derive(
     ds: &DataStruct,
     ident: Ident,
     generics: &Generics,
 ) -> Result<TokenStream, TokenStream> {
-    let TelocStruct {
-        initable,
-        injectable,
-    } = parse_teloc_struct(ds)?;
+    expand(&ds.fields, quote! { Self }, ident, generics)
+}
+
+pub fn derive_on_enum(
+    de: &DataEnum,
+    ident: Ident,
+    generics: &Generics,
+) -> Result<TokenStream, TokenStream> {
+    let variant = get_inject_variant(de)?;
+    let variant_ident = &variant.ident;
+    expand(
+        &variant.fields,
+        quote! { Self::#variant_ident },
+        ident,
+        generics,
+    )
+}
+
+fn expand(
+    item_fields: &Fields,
+    constructor: TokenStream,
+    ident: Ident,
+    generics: &Generics,
+) -> Result<TokenStream, TokenStream> {
+    let TelocFields { fields, injectable } = parse_teloc_fields(item_fields)?;
 
     let impl_block_generics = get_impl_block_generics(generics);
     let struct_block_generics = get_struct_block_generics(generics);
     let where_clause = get_where_clause(generics);
 
-    let init_field = initable.iter().map(|f| &f.field);
-    let init_field_ty = initable.iter().map(|f| &f.field_ty);
-    let init_field_exprs = initable.iter().map(|f| &f.args);
-
-    let ty_dep = injectable.iter().map(|f| f.field_ty);
-    let ty_dep2 = injectable.iter().map(|f| f.field_ty);
+    let ty_dep = injectable.iter().map(|f| &f.field_ty);
+    let ty_dep2 = injectable.iter().map(|f| &f.field_ty);
 
     let mut destructure = quote! { teloc::reexport::frunk::HNil };
-    injectable.iter().map(|f| f.field).rev().for_each(|id| {
+    injectable.iter().map(|f| &f.binding).rev().for_each(|id| {
         destructure = quote! {
             teloc::reexport::frunk::HCons {
                 head: #id,
@@ -37,7 +54,16 @@ pub fn derive(
             }
         };
     });
-    let names = injectable.iter().map(|f| f.field);
+
+    let values = fields.iter().map(|f| f.value());
+    let construct = match item_fields {
+        Fields::Named(_) => {
+            let names = fields.iter().map(|f| f.name);
+            quote! { #constructor { #(#names: #values,)* } }
+        }
+        Fields::Unnamed(_) => quote! { #constructor(#(#values),*) },
+        Fields::Unit => constructor,
+    };
 
     Ok(quote! {
         impl #impl_block_generics
@@ -46,74 +72,91 @@ pub fn derive(
         {
             fn init(deps: teloc::reexport::HList![#(#ty_dep2),*]) -> Self {
                 let #destructure = deps;
-                Self {
-                    #(
-                        #init_field : <#init_field_ty>::init(#init_field_exprs),
-                    )*
-                    #(
-                        #names,
-                    )*
-                }
+                #construct
             }
         }
     })
 }
 
-pub fn derive_on_unit(ident: Ident, generics: &Generics) -> Result<TokenStream, TokenStream> {
-    let impl_block_generics = get_impl_block_generics(generics);
-    let struct_block_generics = get_struct_block_generics(generics);
-    let where_clause = get_where_clause(generics);
+const INJECT_VARIANT_ATTRS: [&str; 2] = ["inject_variant", "default_variant"];
 
-    Ok(quote! {
-        impl #impl_block_generics
-            teloc::Dependency<teloc::reexport::HList![]>
-        for #ident #struct_block_generics #where_clause
-        {
-            fn init(_: teloc::reexport::HList![]) -> Self {
-                #ident
-            }
-        }
-    })
+fn get_inject_variant(de: &DataEnum) -> Result<&Variant, TokenStream> {
+    let variants = de
+        .variants
+        .iter()
+        .filter(|v| {
+            v.attrs.iter().any(|attr| {
+                INJECT_VARIANT_ATTRS
+                    .iter()
+                    .any(|name| attr.path.is_ident(name))
+            })
+        })
+        .collect::<Vec<_>>();
+    match variants.as_slice() {
+        [x] => Ok(x),
+        _ => Err(compile_error(format!(
+            "Expected one variant marked with `#[inject_variant]` or `#[default_variant]` attribute, found {}",
+            variants.len()
+        ))),
+    }
 }
 
-fn parse_teloc_struct(ds: &DataStruct) -> Result<TelocStruct, TokenStream> {
-    let fields = get_fields(ds);
-    let mut initable = vec![];
+fn parse_teloc_fields(fields: &Fields) -> Result<TelocFields<'_>, TokenStream> {
+    let fields = get_fields(fields);
+    // Bindings must not shadow items used in expressions of `#[init(...)]` and `#[init_with(...)]`
+    let mut bindings = name_generator().map(|s| Ident::new(&s, Span::mixed_site()));
+    let mut parsed = vec![];
     let mut injectable = vec![];
+    let mut inject = |field_ty: Type| {
+        let binding = bindings.next().unwrap();
+        injectable.push(InjectableField {
+            field_ty,
+            binding: binding.clone(),
+        });
+        binding
+    };
     for field in fields {
-        match get_1_teloc_attr(field.attrs.as_slice())? {
-            Some(attr) => {
-                match attr.path.get_ident().unwrap().to_string().as_str() {
-                    "init" => {
-                        let teloc = attr
-                            .parse_args::<TelocAttr>()
-                            .map_err(|e| compile_error(e.to_compile_error()))?;
-                        let field_ty = &field.ty;
-                        initable.push(InitableField {
-                            args: teloc.exprs,
-                            field_ty,
-                            field: field.ident.as_ref().unwrap(), // TODO: unnamed fields
-                        })
-                    }
-                    _ => unreachable!(),
+        let field_ty = &field.ty;
+        let kind = match get_1_teloc_attr(field.attrs.as_slice())? {
+            Some(attr) => match attr.path.get_ident().unwrap().to_string().as_str() {
+                "init" => {
+                    let teloc = attr
+                        .parse_args::<TelocAttr>()
+                        .map_err(|e| compile_error(e.to_compile_error()))?;
+                    FieldKind::Init(teloc.exprs)
                 }
-            }
-            None => {
-                injectable.push(InjectableField {
-                    field_ty: &field.ty,
-                    field: field.ident.as_ref().unwrap(), // TODO: unnamed fields
-                })
-            }
-        }
+                "default" => FieldKind::Default,
+                "init_with" => {
+                    let InitWithAttr { func, args } = attr
+                        .parse_args::<InitWithAttr>()
+                        .map_err(|e| compile_error(e.to_compile_error()))?;
+                    let args = args.into_iter().map(&mut inject).collect();
+                    FieldKind::InitWith { func, args }
+                }
+                "resolve" => {
+                    let ResolveAttr { ty } = attr
+                        .parse_args::<ResolveAttr>()
+                        .map_err(|e| compile_error(e.to_compile_error()))?;
+                    FieldKind::Resolve(inject(ty))
+                }
+                _ => unreachable!(),
+            },
+            None => FieldKind::Inject(inject(field_ty.clone())),
+        };
+        parsed.push(TelocField {
+            name: field.ident.as_ref(),
+            field_ty,
+            kind,
+        });
     }
-    Ok(TelocStruct {
-        initable,
+    Ok(TelocFields {
+        fields: parsed,
         injectable,
     })
 }
 
-fn get_fields(ds: &DataStruct) -> Vec<&Field> {
-    match &ds.fields {
+fn get_fields(fields: &Fields) -> Vec<&Field> {
+    match fields {
         Fields::Named(named) => named.named.iter().collect(),
         Fields::Unnamed(unnamed) => unnamed.unnamed.iter().collect(),
         Fields::Unit => vec![],
@@ -131,17 +174,70 @@ impl Parse for TelocAttr {
     }
 }
 
-struct TelocStruct<'a> {
-    initable: Vec<InitableField<'a>>,
-    injectable: Vec<InjectableField<'a>>,
+/// `#[init_with(path::to::func(Arg1, Arg2))]`, parentheses can be omitted when function has no
+/// arguments.
+struct InitWithAttr {
+    func: Path,
+    args: Punctuated<Type, Token![,]>,
+}
+impl Parse for InitWithAttr {
+    fn parse(input: &ParseBuffer) -> Result<Self, syn::Error> {
+        let func = input.parse()?;
+        let args = if input.is_empty() {
+            Punctuated::new()
+        } else {
+            let content;
+            parenthesized!(content in input);
+            content.parse_terminated(Type::parse)?
+        };
+        Ok(Self { func, args })
+    }
 }
 
-struct InitableField<'a> {
-    args: Punctuated<Expr, Token![,]>,
-    field_ty: &'a Type,
-    field: &'a Ident,
+/// `#[resolve(as = Type)]`
+struct ResolveAttr {
+    ty: Type,
 }
-struct InjectableField<'a> {
+impl Parse for ResolveAttr {
+    fn parse(input: &ParseBuffer) -> Result<Self, syn::Error> {
+        input.parse::<Token![as]>()?;
+        input.parse::<Token![=]>()?;
+        Ok(Self { ty: input.parse()? })
+    }
+}
+
+struct TelocFields<'a> {
+    fields: Vec<TelocField<'a>>,
+    injectable: Vec<InjectableField>,
+}
+
+struct TelocField<'a> {
+    name: Option<&'a Ident>,
     field_ty: &'a Type,
-    field: &'a Ident,
+    kind: FieldKind,
+}
+impl TelocField<'_> {
+    fn value(&self) -> TokenStream {
+        let field_ty = self.field_ty;
+        match &self.kind {
+            FieldKind::Init(args) => quote! { <#field_ty>::init(#args) },
+            FieldKind::Default => quote! { <#field_ty as ::core::default::Default>::default() },
+            FieldKind::InitWith { func, args } => quote! { #func(#(#args),*) },
+            FieldKind::Resolve(binding) => quote! { ::core::convert::Into::into(#binding) },
+            FieldKind::Inject(binding) => quote! { #binding },
+        }
+    }
+}
+
+enum FieldKind {
+    Init(Punctuated<Expr, Token![,]>),
+    Default,
+    InitWith { func: Path, args: Vec<Ident> },
+    Resolve(Ident),
+    Inject(Ident),
+}
+
+struct InjectableField {
+    field_ty: Type,
+    binding: Ident,
 }
@@ -0,0 +1,179 @@
+use crate::common::{compile_error, to_camel_case};
+use crate::generics::{get_impl_block_generics, get_where_clause};
+use crate::inject::{destructure, desugar_impl_trait_args};
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::quote;
+use syn::parse::{Parse, ParseBuffer};
+use syn::visit_mut::{self, VisitMut};
+use syn::{
+    parse_quote, FnArg, GenericParam, ItemFn, Lifetime, ParenthesizedGenericArguments, ReturnType,
+    Token, Type, TypeBareFn, TypeReference,
+};
+
+/// Generates the handler struct and its `Invoke` impl.
+pub fn expand(f: &ItemFn, args: &HandlerArgs) -> Result<TokenStream, TokenStream> {
+    if f.sig.asyncness.is_none() {
+        return Err(compile_error("Expected async fn"));
+    }
+    let fn_ident = &f.sig.ident;
+    let vis = &f.vis;
+    let name = match &args.name {
+        Some(name) => name.clone(),
+        None => Ident::new(&to_camel_case(&fn_ident.to_string()), fn_ident.span()),
+    };
+
+    let mut sig = desugar_impl_trait_args(&f.sig);
+    let mut named = NameElidedLifetimes {
+        lifetime: Lifetime::new("'__teloc_handler", Span::call_site()),
+        used: false,
+    };
+    named.visit_signature_mut(&mut sig);
+
+    let mut args_ty = sig.inputs.iter().map(|inp| match inp {
+        FnArg::Receiver(_) => Err(compile_error("Function must not give self as arg!")),
+        FnArg::Typed(pat) => Ok(pat.ty.as_ref()),
+    });
+    let input = match args_ty.next() {
+        Some(input) => input?,
+        None => {
+            return Err(compile_error(
+                "Expected the input of the handler as the first arg, found no args",
+            ))
+        }
+    };
+    let dependencies = args_ty.collect::<Result<Vec<&Type>, _>>()?;
+    let output = match &sig.output {
+        ReturnType::Default => quote! { () },
+        ReturnType::Type(_, ty) => quote! { #ty },
+    };
+
+    // Future returned by the async fn captures all generic params of the function, so they must
+    // outlive the future. The future lives as long as the named elided lifetimes, or as the first
+    // lifetime param of the function, otherwise it is `'static`.
+    let mut generics = sig.generics.clone();
+    let lifetime = if named.used {
+        let lifetime = named.lifetime;
+        generics.params.insert(0, parse_quote! { #lifetime });
+        lifetime
+    } else {
+        match sig.generics.lifetimes().next() {
+            Some(l) => l.lifetime.clone(),
+            None => Lifetime::new("'static", Span::call_site()),
+        }
+    };
+    let where_clause = generics.make_where_clause();
+    for param in &sig.generics.params {
+        match param {
+            GenericParam::Lifetime(l) if l.lifetime != lifetime => {
+                let l = &l.lifetime;
+                where_clause.predicates.push(parse_quote! { #l: #lifetime });
+            }
+            GenericParam::Type(t) => {
+                let t = &t.ident;
+                where_clause.predicates.push(parse_quote! { #t: #lifetime });
+            }
+            _ => {}
+        }
+    }
+    let impl_block_generics = get_impl_block_generics(&generics);
+    let where_clause = get_where_clause(&generics);
+
+    let send = if args.send {
+        quote! { + ::core::marker::Send }
+    } else {
+        quote! {}
+    };
+    let (destructure, names) = destructure(dependencies.len());
+    let doc = format!(
+        "Handler generated from `{}` function by `#[teloc::handler]` macro.",
+        fn_ident
+    );
+
+    Ok(quote! {
+        #[doc = #doc]
+        #[derive(Debug, Clone, Copy)]
+        #vis struct #name;
+
+        impl #impl_block_generics teloc::Invoke<#input, teloc::reexport::HList![#(#dependencies),*]> for #name #where_clause {
+            type Output = #output;
+            type Future = ::core::pin::Pin<teloc::reexport::alloc::boxed::Box<
+                dyn ::core::future::Future<Output = #output> #send + #lifetime
+            >>;
+
+            fn invoke(&self, input: #input, data: teloc::reexport::HList![#(#dependencies),*]) -> Self::Future {
+                let #destructure = data;
+                teloc::reexport::alloc::boxed::Box::pin(#fn_ident(input, #(#names),*))
+            }
+        }
+    })
+}
+
+/// Replaces elided lifetimes in types of args by the named lifetime, because elided lifetimes
+/// cannot be used in the impl header. Lifetimes in `fn` pointers and `Fn` traits are left as is.
+struct NameElidedLifetimes {
+    lifetime: Lifetime,
+    used: bool,
+}
+
+impl VisitMut for NameElidedLifetimes {
+    fn visit_type_reference_mut(&mut self, r: &mut TypeReference) {
+        if r.lifetime.is_none() {
+            r.lifetime = Some(self.lifetime.clone());
+            self.used = true;
+        }
+        visit_mut::visit_type_reference_mut(self, r);
+    }
+
+    fn visit_lifetime_mut(&mut self, l: &mut Lifetime) {
+        if l.ident == "_" {
+            *l = self.lifetime.clone();
+            self.used = true;
+        }
+    }
+
+    fn visit_type_bare_fn_mut(&mut self, _: &mut TypeBareFn) {}
+
+    fn visit_parenthesized_generic_arguments_mut(&mut self, _: &mut ParenthesizedGenericArguments) {
+    }
+}
+
+/// Args of `#[handler]` macro: comma-separated name of the generated struct and `?Send`.
+pub struct HandlerArgs {
+    pub name: Option<Ident>,
+    pub send: bool,
+}
+
+impl Parse for HandlerArgs {
+    fn parse(input: &ParseBuffer) -> Result<Self, syn::Error> {
+        let mut args = Self {
+            name: None,
+            send: true,
+        };
+        while !input.is_empty() {
+            if input.peek(Token![?]) {
+                input.parse::<Token![?]>()?;
+                let ident: Ident = input.parse()?;
+                if ident != "Send" {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        format!("Expected `?Send`, found `?{}`", ident),
+                    ));
+                }
+                args.send = false;
+            } else {
+                let ident: Ident = input.parse()?;
+                if args.name.is_some() {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        "Name of the handler is specified more than once",
+                    ));
+                }
+                args.name = Some(ident);
+            }
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+        Ok(args)
+    }
+}
@@ -1,61 +1,281 @@
 use crate::common::{compile_error, ident_generator};
 use crate::generics::{get_impl_block_generics, get_where_clause};
-use crate::parse::ParseInjectImpl;
-use proc_macro2::TokenStream;
-use quote::{quote, ToTokens};
+use crate::parse::{ParseBuilderImpl, ParseInjectImpl};
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote, ToTokens};
 use syn::parse::{Parse, ParseBuffer};
-use syn::{FnArg, ImplItemMethod, ItemFn, ItemImpl, ReturnType, Type};
+use syn::visit_mut::{self, VisitMut};
+use syn::{
+    parse_quote, FnArg, GenericParam, ImplItemMethod, ItemFn, ItemImpl, ReturnType, Signature,
+    Token, Type,
+};
 
-pub fn expand(input: &InjectInput) -> Result<TokenStream, TokenStream> {
-    let dependencies = input
-        .sig()
-        .inputs
+pub fn expand(input: &InjectInput, args: &InjectArgs) -> Result<TokenStream, TokenStream> {
+    let mut tokens = expand_dependency(input)?;
+    tokens.extend(expand_provides(input, &args.provides)?);
+    if let Some(family) = &args.family {
+        tokens.extend(expand_family(input, family)?);
+    }
+    Ok(tokens)
+}
+
+/// Generates `Provides` impls and `From` impls for `Box` for every trait object from
+/// `provides = dyn Trait` args.
+fn expand_provides(input: &InjectInput, provides: &[Type]) -> Result<TokenStream, TokenStream> {
+    let struct_ty = get_struct_ty(input)?;
+    let generics = match input {
+        InjectInput::Function(f) => desugar_impl_trait_args(&f.sig).generics,
+        _ => input.generics().clone(),
+    };
+    let impl_block_generics = get_impl_block_generics(&generics);
+    let where_clause = get_where_clause(&generics);
+
+    Ok(provides
         .iter()
-        .map(|inp| match inp {
-            FnArg::Receiver(_) => Err(compile_error("Function must not give self as arg!")),
-            FnArg::Typed(pat) => Ok(pat.ty.as_ref()),
+        .map(|interface| {
+            quote! {
+                impl #impl_block_generics teloc::Provides<#interface> for #struct_ty #where_clause {
+                    fn provide_box(self: teloc::reexport::alloc::boxed::Box<Self>) -> teloc::reexport::alloc::boxed::Box<#interface> {
+                        self
+                    }
+                    fn provide_rc(self: teloc::reexport::alloc::rc::Rc<Self>) -> teloc::reexport::alloc::rc::Rc<#interface> {
+                        self
+                    }
+                    fn provide_arc(self: teloc::reexport::alloc::sync::Arc<Self>) -> teloc::reexport::alloc::sync::Arc<#interface> {
+                        self
+                    }
+                }
+
+                impl #impl_block_generics ::core::convert::From<teloc::reexport::alloc::boxed::Box<#struct_ty>> for teloc::reexport::alloc::boxed::Box<#interface> #where_clause {
+                    fn from(x: teloc::reexport::alloc::boxed::Box<#struct_ty>) -> Self {
+                        x
+                    }
+                }
+            }
         })
-        .collect::<Result<Vec<_>, _>>()?;
+        .collect())
+}
 
+/// Generates `FamilyMember` impl from `family = Family` arg.
+fn expand_family(input: &InjectInput, family: &Type) -> Result<TokenStream, TokenStream> {
     let struct_ty = get_struct_ty(input)?;
-    let fn_ident = &input.sig().ident;
+    let generics = input.generics();
+    let impl_block_generics = get_impl_block_generics(generics);
+    let where_clause = get_where_clause(generics);
 
-    let generics = &input.generics();
-    let impl_block_generics = generics.map(get_impl_block_generics);
-    let where_clause = generics.map(get_where_clause);
+    Ok(quote! {
+        impl #impl_block_generics teloc::FamilyMember for #struct_ty #where_clause {
+            type Family = #family;
+        }
+    })
+}
 
-    let mut destructure = quote! { teloc::reexport::frunk::HNil };
-    ident_generator(dependencies.len())
-        .into_iter()
-        .rev()
-        .for_each(|id| {
-            destructure = quote! {
-                teloc::reexport::frunk::HCons {
-                    head: #id,
-                    tail: #destructure
+fn expand_dependency(input: &InjectInput) -> Result<TokenStream, TokenStream> {
+    let struct_ty = get_struct_ty(input)?;
+
+    let generics = input.generics();
+    let impl_block_generics = get_impl_block_generics(generics);
+    let where_clause = get_where_clause(generics);
+
+    match input {
+        InjectInput::Impl(imp) => {
+            let mut tokens = quote! {};
+            for (i, init_method) in imp.init_methods.iter().enumerate() {
+                let fn_ident = &init_method.method.sig.ident;
+                let Constructor { dependencies, init } = get_constructor(
+                    quote! { <#struct_ty>::#fn_ident },
+                    &init_method.method.sig,
+                    imp.post_construct.as_ref(),
+                )?;
+                if i == 0 {
+                    tokens.extend(quote! {
+                        impl #impl_block_generics teloc::Dependency<teloc::reexport::HList![#(#dependencies),*]> for #struct_ty #where_clause {
+                            fn init(data: teloc::reexport::HList![#(#dependencies),*]) -> Self {
+                                #init
+                            }
+                        }
+                    });
                 }
-            };
-        });
-    let names = ident_generator(dependencies.len());
+                if let Some(marker) = &init_method.marker {
+                    let vis = &init_method.method.vis;
+                    tokens.extend(quote! {
+                        #[derive(Debug)]
+                        #vis struct #marker;
 
-    let init = match input {
-        InjectInput::Impl(_, _) => quote! { <#struct_ty>::#fn_ident(#(#names),*) },
-        InjectInput::Function(_) => quote! { #fn_ident(#(#names),*) },
-    };
+                        impl #impl_block_generics teloc::Constructor<#struct_ty, teloc::reexport::HList![#(#dependencies),*]> for #marker #where_clause {
+                            fn construct(data: teloc::reexport::HList![#(#dependencies),*]) -> #struct_ty {
+                                #init
+                            }
+                        }
+                    });
+                }
+            }
+            Ok(tokens)
+        }
+        InjectInput::Builder(builder) => {
+            let builder_ty = &builder.item_impl.self_ty;
+            let methods = std::iter::once(&builder.init_method)
+                .chain(builder.steps.iter())
+                .chain(std::iter::once(&builder.build_method))
+                .collect::<Vec<_>>();
+            let dependencies = methods
+                .iter()
+                .flat_map(|method| method_dependencies(Some(method)))
+                .collect::<Vec<_>>();
+            let (destructure, mut names) = destructure(dependencies.len());
 
-    Ok(quote! {
-        impl #impl_block_generics teloc::Dependency<teloc::reexport::HList![#(#dependencies),*]> for #struct_ty #where_clause {
-            fn init(data: teloc::reexport::HList![#(#dependencies),*]) -> Self {
-                let #destructure = data;
-                #init
+            let mut init = quote! { <#builder_ty> };
+            let mut separator = quote! { :: };
+            for method in methods {
+                let ident = &method.sig.ident;
+                let rest = names.split_off(method_dependencies(Some(method)).count());
+                init = quote! { #init #separator #ident(#(#names),*) };
+                separator = quote! { . };
+                names = rest;
+            }
+
+            Ok(quote! {
+                impl #impl_block_generics teloc::Dependency<teloc::reexport::HList![#(#dependencies),*]> for #struct_ty #where_clause {
+                    fn init(data: teloc::reexport::HList![#(#dependencies),*]) -> Self {
+                        let #destructure = data;
+                        #init
+                    }
+                }
+            })
+        }
+        InjectInput::Function(f) => {
+            let fn_ident = &f.sig.ident;
+            let sig = desugar_impl_trait_args(&f.sig);
+            let impl_block_generics = get_impl_block_generics(&sig.generics);
+            let where_clause = get_where_clause(&sig.generics);
+            let Constructor { dependencies, init } =
+                get_constructor(quote! { #fn_ident }, &sig, None)?;
+            Ok(quote! {
+                impl #impl_block_generics teloc::Dependency<teloc::reexport::HList![#(#dependencies),*]> for #struct_ty #where_clause {
+                    fn init(data: teloc::reexport::HList![#(#dependencies),*]) -> Self {
+                        #init
+                    }
+                }
+            })
+        }
+    }
+}
+
+struct Constructor<'a> {
+    dependencies: Vec<&'a Type>,
+    init: TokenStream,
+}
+
+/// Generates body of the constructor that destructures `data` and calls `func` and the
+/// `post_construct` method with the dependencies.
+fn get_constructor<'a>(
+    func: TokenStream,
+    sig: &'a Signature,
+    post_construct: Option<&'a ImplItemMethod>,
+) -> Result<Constructor<'a>, TokenStream> {
+    let dependencies = init_method_dependencies(sig)?;
+    let post_dependencies = method_dependencies(post_construct).collect::<Vec<_>>();
+
+    let (destructure, mut names) = destructure(dependencies.len() + post_dependencies.len());
+    let post_names = names.split_off(dependencies.len());
+
+    let init = quote! { #func(#(#names),*) };
+    let init = match post_construct {
+        Some(method) => {
+            let post_ident = &method.sig.ident;
+            let mutability = method.sig.receiver().and_then(|r| match r {
+                FnArg::Receiver(r) => r.mutability,
+                FnArg::Typed(_) => None,
+            });
+            quote! {
+                let #mutability this = #init;
+                this.#post_ident(#(#post_names),*);
+                this
             }
         }
+        None => init,
+    };
+
+    Ok(Constructor {
+        dependencies: dependencies.into_iter().chain(post_dependencies).collect(),
+        init: quote! {
+            let #destructure = data;
+            #init
+        },
     })
 }
 
+/// Replaces `impl Trait` in types of args of the function by new generic params of the function,
+/// because `impl Trait` cannot be used in the `Dependency` impl header.
+pub fn desugar_impl_trait_args(sig: &Signature) -> Signature {
+    struct Desugar {
+        params: Vec<GenericParam>,
+    }
+    impl VisitMut for Desugar {
+        fn visit_type_mut(&mut self, ty: &mut Type) {
+            visit_mut::visit_type_mut(self, ty);
+            if let Type::ImplTrait(impl_trait) = ty {
+                let ident = format_ident!("__TelocImpl{}", self.params.len());
+                let bounds = &impl_trait.bounds;
+                self.params.push(parse_quote! { #ident: #bounds });
+                *ty = parse_quote! { #ident };
+            }
+        }
+    }
+
+    let mut sig = sig.clone();
+    let mut desugar = Desugar { params: vec![] };
+    sig.inputs
+        .iter_mut()
+        .for_each(|inp| desugar.visit_fn_arg_mut(inp));
+    sig.generics.params.extend(desugar.params);
+    sig
+}
+
+fn init_method_dependencies(sig: &Signature) -> Result<Vec<&Type>, TokenStream> {
+    sig.inputs
+        .iter()
+        .map(|inp| match inp {
+            FnArg::Receiver(_) => Err(compile_error("Function must not give self as arg!")),
+            FnArg::Typed(pat) => Ok(pat.ty.as_ref()),
+        })
+        .collect()
+}
+
+/// Generates pattern that destructures HList of `count` elements and names of the bindings.
+pub fn destructure(count: usize) -> (TokenStream, Vec<Ident>) {
+    let mut destructure = quote! { teloc::reexport::frunk::HNil };
+    ident_generator(count).into_iter().rev().for_each(|id| {
+        destructure = quote! {
+            teloc::reexport::frunk::HCons {
+                head: #id,
+                tail: #destructure
+            }
+        };
+    });
+    (destructure, ident_generator(count))
+}
+
+/// Types of the args of method, except `self`.
+fn method_dependencies(method: Option<&ImplItemMethod>) -> impl Iterator<Item = &Type> {
+    method
+        .into_iter()
+        .flat_map(|method| method.sig.inputs.iter())
+        .filter_map(|inp| match inp {
+            FnArg::Receiver(_) => None,
+            FnArg::Typed(pat) => Some(pat.ty.as_ref()),
+        })
+}
+
 fn get_struct_ty(inp: &InjectInput) -> Result<&Type, TokenStream> {
     match inp {
-        InjectInput::Impl(x, _) => Ok(x.self_ty.as_ref()),
+        InjectInput::Impl(x) => Ok(x.item_impl.self_ty.as_ref()),
+        InjectInput::Builder(x) => match &x.build_method.sig.output {
+            ReturnType::Default => Err(compile_error(
+                "Expected return type of build method, found default return type",
+            )),
+            ReturnType::Type(_, ty) => Ok(ty.as_ref()),
+        },
         InjectInput::Function(f) => match &f.sig.output {
             ReturnType::Default => Err(compile_error(
                 "Expected return type, found default return type",
@@ -65,36 +285,74 @@ fn get_struct_ty(inp: &InjectInput) -> Result<&Type, TokenStream> {
     }
 }
 
+/// Args of `#[inject]` macro: comma-separated `builder` and `provides = Type`.
+pub struct InjectArgs {
+    pub builder: bool,
+    pub provides: Vec<Type>,
+    pub family: Option<Type>,
+}
+
+impl Parse for InjectArgs {
+    fn parse(input: &ParseBuffer) -> Result<Self, syn::Error> {
+        let mut args = Self {
+            builder: false,
+            provides: vec![],
+            family: None,
+        };
+        while !input.is_empty() {
+            let ident: Ident = input.parse()?;
+            if ident == "builder" {
+                args.builder = true;
+            } else if ident == "provides" {
+                input.parse::<Token![=]>()?;
+                args.provides.push(input.parse()?);
+            } else if ident == "family" {
+                input.parse::<Token![=]>()?;
+                args.family = Some(input.parse()?);
+            } else {
+                return Err(syn::Error::new(
+                    ident.span(),
+                    format!(
+                        "Expected `builder`, `provides` or `family`, found `{}`",
+                        ident
+                    ),
+                ));
+            }
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+        Ok(args)
+    }
+}
+
 pub enum InjectInput {
-    Impl(ItemImpl, Box<ImplItemMethod>),
-    Function(ItemFn),
+    Impl(Box<ParseInjectImpl>),
+    Builder(Box<ParseBuilderImpl>),
+    Function(Box<ItemFn>),
 }
 impl InjectInput {
-    fn sig(&self) -> &syn::Signature {
+    fn generics(&self) -> &syn::Generics {
         match self {
-            InjectInput::Impl(_, x) => &x.sig,
-            InjectInput::Function(x) => &x.sig,
+            InjectInput::Impl(x) => &x.item_impl.generics,
+            InjectInput::Builder(x) => &x.item_impl.generics,
+            InjectInput::Function(x) => &x.sig.generics,
         }
     }
-    fn generics(&self) -> Option<&syn::Generics> {
-        match self {
-            InjectInput::Impl(x, _) => Some(&x.generics),
-            InjectInput::Function(_) => None,
-        }
+
+    pub fn parse_builder(input: &ParseBuffer) -> Result<Self, syn::Error> {
+        let item_impl: ItemImpl = input.parse()?;
+        Ok(Self::Builder(Box::new(ParseBuilderImpl::parse(item_impl)?)))
     }
 }
 
 impl Parse for InjectInput {
     fn parse(input: &ParseBuffer) -> Result<Self, syn::Error> {
         if let Ok(f) = input.parse::<ItemFn>() {
-            Ok(Self::Function(f))
+            Ok(Self::Function(Box::new(f)))
         } else {
             let item_impl: ItemImpl = input.parse()?;
-            let ParseInjectImpl {
-                item_impl,
-                init_method,
-            } = ParseInjectImpl::parse(item_impl)?;
-            Ok(Self::Impl(item_impl, Box::new(init_method)))
+            Ok(Self::Impl(Box::new(ParseInjectImpl::parse(item_impl)?)))
         }
     }
 }
@@ -102,7 +360,8 @@ impl Parse for InjectInput {
 impl ToTokens for InjectInput {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         match self {
-            InjectInput::Impl(x, _) => x.to_tokens(tokens),
+            InjectInput::Impl(x) => x.item_impl.to_tokens(tokens),
+            InjectInput::Builder(x) => x.item_impl.to_tokens(tokens),
             InjectInput::Function(x) => x.to_tokens(tokens),
         }
     }
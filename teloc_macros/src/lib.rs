@@ -1,6 +1,7 @@
 mod common;
 mod derive_teloc;
 mod generics;
+mod handler;
 mod inject;
 mod parse;
 
@@ -12,15 +13,27 @@ use crate::common::compile_error;
 use proc_macro::TokenStream;
 use std::convert::identity;
 use syn::Data;
-use syn::{parse_macro_input, DeriveInput};
+use syn::{parse_macro_input, DeriveInput, ItemFn};
 
-/// Derive macro can be used on structs with **named fields** when all fields implements `Dependency` trait or
-/// fields described using `#[init(...)]` attr.
+/// Derive macro can be used on structs and enums when all fields implements `Dependency` trait or
+/// fields described using `#[init(...)]` attr. Structs can have named fields, unnamed fields (tuple
+/// structs) or no fields at all.
 /// We do not recommend using this macro in production code.
 ///
-/// By default macro define all fields as dependencies, but you can initialize field by yourself
-/// using attribute `#[init]`. In curly braces you must define a parameters, that will be passed
-/// to calling `FieldType::init` method.
+/// By default macro define all fields as dependencies, but you can change how the field is
+/// initialized using one of the attributes:
+/// - `#[init(...)]`. In curly braces you must define a parameters, that will be passed to calling
+///   `FieldType::init` method.
+/// - `#[default]`. Field will be initialized using `Default::default()`.
+/// - `#[init_with(path::to::func(Arg1, Arg2))]`. Field will be initialized by calling `func` with
+///   arguments of types `Arg1` and `Arg2` resolved from the provider. Parentheses can be omitted
+///   when function has no arguments.
+/// - `#[resolve(as = Type)]`. `Type` will be resolved from the provider and converted into the
+///   field type using `Into` trait. Useful for `Box<dyn Trait>` fields.
+///
+/// For enums you must mark the variant that will be constructed by `#[inject_variant]` attribute
+/// (or by its alias `#[default_variant]`). Exactly one variant must be marked, and fields of the
+/// variant are handled in the same way as fields of structs. Unions are not supported.
 ///
 /// Example:
 /// ```compile_fail
@@ -37,18 +50,28 @@ use syn::{parse_macro_input, DeriveInput};
 ///     a: Number
 /// }
 ///
+/// fn make_number(foo: &Foo) -> Number { Number(foo.a.0 + 1) }
+///
+/// #[derive(Teloc)]
+/// struct Bar(Foo, #[default] u8, #[init_with(make_number(&Foo))] Number);
+///
 /// #[derive(Teloc)]
-/// struct Bar {
-///     foo: Foo,
+/// enum Baz {
+///     #[inject_variant]
+///     Bar(Bar),
+///     Empty,
 /// }
 /// ```
-#[proc_macro_derive(Dependency, attributes(init))]
+#[proc_macro_derive(
+    Dependency,
+    attributes(init, default, init_with, resolve, inject_variant, default_variant)
+)]
 pub fn derive_teloc(tokens: TokenStream) -> TokenStream {
     let input = parse_macro_input!(tokens as DeriveInput);
     let res = match input.data {
         Data::Struct(ds) => derive_teloc::derive(&ds, input.ident, &input.generics),
-        Data::Enum(_) => return compile_error("Expected struct, found enum").into(),
-        Data::Union(_) => derive_teloc::derive_on_unit(input.ident, &input.generics),
+        Data::Enum(de) => derive_teloc::derive_on_enum(&de, input.ident, &input.generics),
+        Data::Union(_) => return compile_error("Expected struct or enum, found union").into(),
     };
     res.unwrap_or_else(identity).into()
 }
@@ -58,6 +81,38 @@ pub fn derive_teloc(tokens: TokenStream) -> TokenStream {
 /// macro.
 /// We recommend using this macro in production code.
 ///
+/// If impl has more than one method, the constructor must be marked by `#[inject::init]`
+/// attribute. Several methods can be marked as constructors: the first of them is used for the
+/// `Dependency` impl, and for each of them a marker struct is generated (named as the type followed
+/// by the method in `CamelCase`, e.g. `ClockNew`, or as specified in `#[inject::init(Name)]`) that
+/// can be passed to `ServiceProvider::add_transient_with` or `ServiceProvider::add_singleton_with`
+/// for choosing the constructor.
+///
+/// Method marked by `#[inject::post_construct]` attribute will be called after the constructor.
+/// It must take `&self` or `&mut self` as first arg, other args will be resolved from the provider
+/// like the constructor args.
+///
+/// `#[inject(builder)]` can be used on an impl of a builder. In this case the service is created
+/// by the chain of calls `Builder::new(..).with_a(..).with_b(..).build(..)` where:
+/// - `new` is a method without `self` arg. It must be marked by `#[inject::init]` if there are
+///   more than one such method.
+/// - `with_*` are methods marked by `#[inject::step]` attribute, in order of declaration. They
+///   must take `self` by value and return `Self`. Other such methods are not called.
+/// - `build` is a method that takes `self` by value and returns the service. It must be marked
+///   by `#[inject::build]` if there are more than one such method.
+///
+/// Args of all these methods will be resolved from the provider. Other methods are ignored.
+///
+/// `#[inject(provides = dyn Trait)]` generates `teloc::Provides<dyn Trait>` impl and
+/// `From<Box<Type>>` impl for `Box<dyn Trait>`, so the service can be registered by
+/// `ServiceProvider::add_transient_as::<dyn Trait, Type>()` or by
+/// `ServiceProvider::add_transient_c::<Box<dyn Trait>, Box<Type>>()`. `provides` can be specified
+/// several times and can be combined with `builder` arg, separated by commas.
+///
+/// `#[inject(family = Family)]` generates `teloc::FamilyMember` impl with `Family` family, so all
+/// services of this generic type can be registered at once by
+/// `ServiceProvider::add_generic::<Family>()`.
+///
 /// Example:
 /// ```compile_fail
 /// use teloc::inject;
@@ -75,11 +130,74 @@ pub fn derive_teloc(tokens: TokenStream) -> TokenStream {
 /// fn create_foo(number: Number) -> Foo {
 ///     Foo { a: number }
 /// }
+///
+/// struct Counter(u8);
+/// #[inject]
+/// impl Counter {
+///     #[inject::init]
+///     fn new() -> Self { Counter(0) }
+///     #[inject::post_construct]
+///     fn start(&mut self, number: &Number) { self.0 = number.0 }
+/// }
+///
+/// trait Count { fn count(&self) -> u8; }
+/// impl Count for Counter { fn count(&self) -> u8 { self.0 } }
+///
+/// struct CounterBuilder(u8);
+/// #[inject(builder, provides = dyn Count)]
+/// impl CounterBuilder {
+///     fn new() -> Self { CounterBuilder(0) }
+///     fn with_number(self, number: &Number) -> Self { CounterBuilder(number.0) }
+///     fn build(self) -> Counter { Counter(self.0) }
+/// }
 /// ```
 #[proc_macro_attribute]
-pub fn inject(_: TokenStream, input: TokenStream) -> TokenStream {
-    let imp = parse_macro_input!(input as inject::InjectInput);
-    let res = inject::expand(&imp);
+pub fn inject(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(args as inject::InjectArgs);
+    let imp = if args.builder {
+        parse_macro_input!(input with inject::InjectInput::parse_builder)
+    } else {
+        parse_macro_input!(input as inject::InjectInput)
+    };
+    let res = inject::expand(&imp, &args);
     let tokens = res.unwrap_or_else(identity);
     (quote::quote! { #imp #tokens }).into()
 }
+
+/// Macro can be used on async functions that handle some input, for example a message from a
+/// queue. It generates a unit struct named as the function in `CamelCase` (or as specified in
+/// `#[handler(Name)]`) that implements `teloc::Invoke<Input, Deps>` trait, where `Input` is the
+/// type of the first arg of the function and `Deps` are types of the other args, which will be
+/// resolved from the provider. The handler can be called by `ServiceProvider::invoke` method, and
+/// handlers with the same input can be dispatched by name using `teloc::HandlerRouter`.
+///
+/// The generated struct returns boxed future that must be `Send`. Use `#[handler(?Send)]` when
+/// the future is not `Send`, for example because it holds an `Rc`.
+///
+/// Example:
+/// ```compile_fail
+/// use teloc::handler;
+///
+/// struct Order(u32);
+///
+/// struct Repo;
+/// #[inject]
+/// impl Repo {
+///     fn new() -> Self { Repo }
+/// }
+///
+/// #[handler]
+/// async fn process(order: Order, repo: &Repo) -> u32 {
+///     order.0
+/// }
+///
+/// #[handler(ProcessLocal, ?Send)]
+/// async fn process_local(order: Order, repo: std::rc::Rc<Repo>) {}
+/// ```
+#[proc_macro_attribute]
+pub fn handler(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(args as handler::HandlerArgs);
+    let f = parse_macro_input!(input as ItemFn);
+    let tokens = handler::expand(&f, &args).unwrap_or_else(identity);
+    (quote::quote! { #f #tokens }).into()
+}
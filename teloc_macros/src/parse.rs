@@ -1,20 +1,39 @@
 use itertools::{Either, Itertools};
-use proc_macro2::Span;
+use proc_macro2::{Ident, Span};
 use syn::spanned::Spanned;
-use syn::{parse_str, ImplItem, ImplItemMethod, ItemImpl, Path};
+use syn::{
+    parse_str, Attribute, FnArg, ImplItem, ImplItemMethod, ItemImpl, Path, ReturnType, Type,
+};
 
-use crate::common::strip_annotation_by_path;
+use crate::common::{strip_annotation_by_path, to_camel_case};
 
 pub struct ParseInjectImpl {
+    pub item_impl: ItemImpl,
+    pub init_methods: Vec<InitMethod>,
+    pub post_construct: Option<ImplItemMethod>,
+}
+
+/// Constructor of the type. `marker` is a name of the struct that must be generated for choosing
+/// this constructor in `ServiceProvider::add_transient_with`.
+pub struct InitMethod {
+    pub method: ImplItemMethod,
+    pub marker: Option<Ident>,
+}
+
+pub struct ParseBuilderImpl {
     pub item_impl: ItemImpl,
     pub init_method: ImplItemMethod,
+    pub steps: Vec<ImplItemMethod>,
+    pub build_method: ImplItemMethod,
 }
 
-impl ParseInjectImpl {
-    const INIT_ANNOTATION_STR: &'static str = "inject::init";
+impl ParseBuilderImpl {
+    const BUILD_ANNOTATION_STR: &'static str = "inject::build";
+    const STEP_ANNOTATION_STR: &'static str = "inject::step";
 
     pub fn parse(item_impl: ItemImpl) -> syn::Result<Self> {
         let span = item_impl.span();
+        let self_ty = item_impl.self_ty.clone();
         let (methods, rest): (Vec<_>, Vec<_>) =
             item_impl
                 .items
@@ -24,14 +43,87 @@ impl ParseInjectImpl {
                     other => Either::Right(other),
                 });
 
-        let init_method = Self::get_annotated_method(span, &methods)?;
-        let init_method = if let Some(init_method) = init_method {
-            init_method
-        } else {
-            Self::get_only_method(span, &methods)?
+        let (constructors, by_value): (Vec<_>, Vec<_>) = methods
+            .iter()
+            .filter(|method| match method.sig.receiver() {
+                None => true,
+                Some(FnArg::Receiver(receiver)) => receiver.reference.is_none(),
+                Some(FnArg::Typed(_)) => false,
+            })
+            .partition(|method| method.sig.receiver().is_none());
+
+        let init_method = ParseInjectImpl::get_annotated_method(
+            span,
+            &methods,
+            ParseInjectImpl::INIT_ANNOTATION_STR,
+        )?;
+        let init_method = match (init_method, constructors.as_slice()) {
+            (Some(method), _) => method,
+            (None, [method]) => (*method).clone(),
+            (None, _) => {
+                return Err(syn::Error::new(
+                    span,
+                    format!(
+                        "Expected one method without `self` arg or one method annotated with #[{}] in builder impl!",
+                        ParseInjectImpl::INIT_ANNOTATION_STR
+                    ),
+                ))
+            }
+        };
+
+        let builds = by_value
+            .into_iter()
+            .filter(|method| !Self::returns_self(method, &self_ty))
+            .cloned()
+            .collect::<Vec<_>>();
+        let build_method =
+            ParseInjectImpl::get_annotated_method(span, &methods, Self::BUILD_ANNOTATION_STR)?;
+        let build_method = match (build_method, builds.as_slice()) {
+            (Some(method), _) => method,
+            (None, [method]) => method.clone(),
+            (None, _) => {
+                return Err(syn::Error::new(
+                    span,
+                    format!(
+                        "Expected one method that takes `self` and does not return `Self` or one method annotated with #[{}] in builder impl!",
+                        Self::BUILD_ANNOTATION_STR
+                    ),
+                ))
+            }
         };
 
-        let methods = strip_annotation_by_path(methods, Self::init_annotation_path())
+        let step_path = ParseInjectImpl::annotation_path(Self::STEP_ANNOTATION_STR);
+        let steps = methods
+            .iter()
+            .filter(|method| method.attrs.iter().any(|attr| attr.path == step_path))
+            .map(|method| {
+                let by_value = matches!(
+                    method.sig.receiver(),
+                    Some(FnArg::Receiver(receiver)) if receiver.reference.is_none()
+                );
+                if by_value && Self::returns_self(method, &self_ty) {
+                    Ok(method.clone())
+                } else {
+                    Err(syn::Error::new(
+                        method.sig.span(),
+                        format!(
+                            "Method annotated with #[{}] must take `self` by value and return `Self`!",
+                            Self::STEP_ANNOTATION_STR
+                        ),
+                    ))
+                }
+            })
+            .collect::<syn::Result<Vec<_>>>()?;
+
+        let methods = strip_annotation_by_path(
+            methods,
+            ParseInjectImpl::annotation_path(ParseInjectImpl::INIT_ANNOTATION_STR),
+        );
+        let methods = strip_annotation_by_path(
+            methods,
+            ParseInjectImpl::annotation_path(Self::BUILD_ANNOTATION_STR),
+        );
+        let methods = strip_annotation_by_path(methods, step_path)
             .into_iter()
             .map(ImplItem::Method);
         let items = rest.into_iter().chain(methods).collect();
@@ -39,20 +131,148 @@ impl ParseInjectImpl {
         Ok(Self {
             item_impl: ItemImpl { items, ..item_impl },
             init_method,
+            steps,
+            build_method,
+        })
+    }
+
+    fn returns_self(method: &ImplItemMethod, self_ty: &Type) -> bool {
+        match &method.sig.output {
+            ReturnType::Default => false,
+            ReturnType::Type(_, ty) => {
+                let is_self = match ty.as_ref() {
+                    Type::Path(path) => path.qself.is_none() && path.path.is_ident("Self"),
+                    _ => false,
+                };
+                is_self || ty.as_ref() == self_ty
+            }
+        }
+    }
+}
+
+impl ParseInjectImpl {
+    const INIT_ANNOTATION_STR: &'static str = "inject::init";
+    const POST_CONSTRUCT_ANNOTATION_STR: &'static str = "inject::post_construct";
+
+    pub fn parse(item_impl: ItemImpl) -> syn::Result<Self> {
+        let span = item_impl.span();
+        let (methods, rest): (Vec<_>, Vec<_>) =
+            item_impl
+                .items
+                .into_iter()
+                .partition_map(|item| match item {
+                    ImplItem::Method(method) => Either::Left(method),
+                    other => Either::Right(other),
+                });
+
+        let post_construct =
+            Self::get_annotated_method(span, &methods, Self::POST_CONSTRUCT_ANNOTATION_STR)?;
+        if let Some(post_construct) = &post_construct {
+            Self::check_post_construct(post_construct)?;
+        }
+
+        let init_methods = Self::get_init_methods(&item_impl.self_ty, &methods)?;
+        let init_methods = if init_methods.is_empty() {
+            vec![InitMethod {
+                method: Self::get_only_method(span, &methods, post_construct.as_ref())?,
+                marker: None,
+            }]
+        } else {
+            init_methods
+        };
+
+        let methods =
+            strip_annotation_by_path(methods, Self::annotation_path(Self::INIT_ANNOTATION_STR));
+        let methods = strip_annotation_by_path(
+            methods,
+            Self::annotation_path(Self::POST_CONSTRUCT_ANNOTATION_STR),
+        )
+        .into_iter()
+        .map(ImplItem::Method);
+        let items = rest.into_iter().chain(methods).collect();
+
+        Ok(Self {
+            item_impl: ItemImpl { items, ..item_impl },
+            init_methods,
+            post_construct,
         })
     }
 
+    fn get_init_methods(
+        self_ty: &Type,
+        methods: &[ImplItemMethod],
+    ) -> syn::Result<Vec<InitMethod>> {
+        let annotation_path = Self::annotation_path(Self::INIT_ANNOTATION_STR);
+        let annotated = methods
+            .iter()
+            .filter_map(|method| {
+                method
+                    .attrs
+                    .iter()
+                    .find(|attr| attr.path == annotation_path)
+                    .map(|attr| (method, attr))
+            })
+            .collect::<Vec<_>>();
+        let several = annotated.len() > 1;
+
+        annotated
+            .into_iter()
+            .map(|(method, attr)| {
+                let marker = match Self::get_marker_name(attr)? {
+                    Some(marker) => Some(marker),
+                    None if several => Some(Ident::new(
+                        &format!(
+                            "{}{}",
+                            Self::type_name(self_ty),
+                            to_camel_case(&method.sig.ident.to_string())
+                        ),
+                        method.sig.ident.span(),
+                    )),
+                    None => None,
+                };
+                Ok(InitMethod {
+                    method: method.clone(),
+                    marker,
+                })
+            })
+            .collect()
+    }
+
+    /// Name of the type used as a prefix of generated markers, so markers of different types in
+    /// one module do not conflict.
+    fn type_name(ty: &Type) -> String {
+        match ty {
+            Type::Path(path) => path
+                .path
+                .segments
+                .last()
+                .map(|segment| segment.ident.to_string())
+                .unwrap_or_default(),
+            _ => String::new(),
+        }
+    }
+
+    fn get_marker_name(attr: &Attribute) -> syn::Result<Option<Ident>> {
+        if attr.tokens.is_empty() {
+            Ok(None)
+        } else {
+            attr.parse_args().map(Some)
+        }
+    }
+
     fn get_annotated_method(
         span: Span,
         methods: &[ImplItemMethod],
+        annotation: &str,
     ) -> syn::Result<Option<ImplItemMethod>> {
+        let annotation_path = Self::annotation_path(annotation);
         let annotated_methods = methods
             .iter()
             .flat_map(|method| {
                 match method
                     .attrs
                     .iter()
-                    .find(|attr| attr.path == Self::init_annotation_path())
+                    .find(|attr| attr.path == annotation_path)
                 {
                     Some(_) => Some(method),
                     _ => None,
@@ -67,20 +287,41 @@ impl ParseInjectImpl {
                 span,
                 format!(
                     "Found more than one method annotated with #[{}] in impl!",
-                    Self::INIT_ANNOTATION_STR
+                    annotation
                 ),
             )),
         }
     }
 
-    fn get_only_method(span: Span, methods: &[ImplItemMethod]) -> syn::Result<ImplItemMethod> {
-        match methods {
+    fn get_only_method(
+        span: Span,
+        methods: &[ImplItemMethod],
+        post_construct: Option<&ImplItemMethod>,
+    ) -> syn::Result<ImplItemMethod> {
+        let methods = methods
+            .iter()
+            .filter(|method| Some(*method) != post_construct)
+            .collect::<Vec<_>>();
+        match methods.as_slice() {
             [method] => Ok((*method).clone()),
             _ => Err(syn::Error::new(span, "Expected one method in impl!")),
         }
     }
 
-    fn init_annotation_path() -> Path {
-        parse_str(Self::INIT_ANNOTATION_STR).unwrap()
+    fn check_post_construct(method: &ImplItemMethod) -> syn::Result<()> {
+        match method.sig.receiver() {
+            Some(FnArg::Receiver(receiver)) if receiver.reference.is_some() => Ok(()),
+            _ => Err(syn::Error::new(
+                method.sig.span(),
+                format!(
+                    "Method annotated with #[{}] must take `&self` or `&mut self` as first arg!",
+                    Self::POST_CONSTRUCT_ANNOTATION_STR
+                ),
+            )),
+        }
+    }
+
+    fn annotation_path(annotation: &str) -> Path {
+        parse_str(annotation).unwrap()
     }
 }